@@ -2,18 +2,28 @@ use wasm_bindgen::prelude::*;
 use serde_json::json;
 
 use logic_parser::lexing::Lexer;
-use logic_parser::parsing::{Parser, ASTNode};
-use logic_parser::errors::{LexerError, ParserError};
+use logic_parser::parsing::{Parser, ASTNode, parse_recovering};
+use logic_parser::diagnostics::{Diagnostic, SourceError};
 use logic_parser::svg_generation::render::render_to_svg;
+use logic_parser::svg_generation::bdd::render_bdd_to_svg;
+use logic_parser::svg_generation::karnaugh::render_karnaugh_to_svg;
 
-macro_rules! generate_json_error {
-    ($span: expr, $error: expr) => {
-        json!({
-            "status": "error",
-            "span": [$span.start, $span.end],
-            "error": $error.to_string()
-        }).to_string()
-    }
+/// Error result with the stable code, the line/column positions, the expected
+/// tokens and the fixes of the [`Diagnostic`], see [`SourceError`] for the list
+/// of codes
+fn generate_json_error<E: SourceError>(error: &E, source: &str) -> String {
+    let diagnostic = Diagnostic::new(error, source);
+    json!({
+        "status": "error",
+        "span": [diagnostic.span.start, diagnostic.span.end],
+        "error": diagnostic.message,
+        "code": diagnostic.code,
+        "help": diagnostic.help,
+        "start": diagnostic.start,
+        "end": diagnostic.end,
+        "expected": diagnostic.expected,
+        "fixes": diagnostic.fixes
+    }).to_string()
 }
 
 #[wasm_bindgen]
@@ -28,40 +38,12 @@ pub fn parse_expression(expr: &str) -> String {
     );
     let tokens = match lexer.tokenize(expr) {
         Ok(t) => t,
-        Err(ref e) => {
-            match e {
-                LexerError::SyntaxError(_, span) => {
-                    return generate_json_error!(span, e);
-                },
-                LexerError::UnknownToken(_, span) => {
-                    return generate_json_error!(span, e);
-                }
-            }
-        }
+        Err(ref e) => return generate_json_error(e, expr)
     };
 
     let ast = match Parser::new(&tokens).parse() {
         Ok(ast) => ast,
-        Err(ref e) => {
-            match e {
-                ParserError::UnexpectedEOF(_, span) => {
-                    return generate_json_error!(span, e);
-                },
-                ParserError::UnexpectedToken(_, span) => {
-                    return generate_json_error!(span, e);
-                },
-                ParserError::LexingError(lexer) => {
-                    match lexer {
-                        LexerError::SyntaxError(_, span) => {
-                            return generate_json_error!(span, e);
-                        },
-                        LexerError::UnknownToken(_, span) => {
-                            return generate_json_error!(span, e);
-                        }
-                    }
-                }
-            }
-        }
+        Err(ref e) => return generate_json_error(e, expr)
     };
 
     format!(
@@ -72,6 +54,25 @@ pub fn parse_expression(expr: &str) -> String {
     )
 }
 
+/// Parses the expression without stopping at the first error, for editors.
+/// The AST has `error` nodes where the source couldn't be parsed and every
+/// error is reported as a diagnostic.
+#[wasm_bindgen]
+pub fn parse_expression_recovering(expr: &str) -> String {
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    let mut lexer = Lexer::with_alphabets(
+        |c| c.is_alphanumeric() || c == '_' || c == '-' || c == ':' || c == '*' || c == '/',
+        |c| c.is_alphabetic(),
+    );
+    let recovery = parse_recovering(&mut lexer, expr);
+    let errors: Vec<Diagnostic> = recovery.errors.iter().map(|e| Diagnostic::new(e, expr)).collect();
+
+    json!({
+        "ast": recovery.ast,
+        "errors": errors
+    }).to_string()
+}
+
 #[wasm_bindgen]
 pub fn generate_svg(ast: JsValue, xsep: f32, ysep: f32, radius: f32) -> String {
     std::panic::set_hook(Box::new(console_error_panic_hook::hook));
@@ -85,6 +86,43 @@ pub fn generate_svg(ast: JsValue, xsep: f32, ysep: f32, radius: f32) -> String {
     svg.as_xml()
 }
 
+#[wasm_bindgen]
+pub fn generate_bdd_svg(ast: JsValue, xsep: f32, ysep: f32, radius: f32) -> String {
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+
+    let ast: ASTNode = match serde_wasm_bindgen::from_value(ast) {
+        Ok(ast) => ast,
+        Err(_) => return String::new()
+    };
+
+    // Error nodes have no truth value
+    let (bdd, root) = match ast.to_bdd() {
+        Ok(bdd) => bdd,
+        Err(_) => return String::new()
+    };
+    let svg = render_bdd_to_svg(&bdd, root, xsep, ysep, radius);
+    svg.as_xml()
+}
+
+#[wasm_bindgen]
+pub fn generate_karnaugh_svg(ast: JsValue, cell_size: f32) -> String {
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+
+    let ast: ASTNode = match serde_wasm_bindgen::from_value(ast) {
+        Ok(ast) => ast,
+        Err(_) => return String::new()
+    };
+
+    // Only 2 to 6 identifiers fit in a map, and error nodes have no truth
+    // value
+    let map = match ast.to_karnaugh_map() {
+        Ok(map) => map.with_groups(),
+        Err(_) => return String::new()
+    };
+    let svg = render_karnaugh_to_svg(&map, cell_size);
+    svg.as_xml()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -96,7 +134,52 @@ mod test {
         assert_json!(result.as_str(), {
             "error": "Unexpected token: R_PAREN",
             "span": [0, 1],
-            "status": "error"
+            "status": "error",
+            "code": "E0201"
+        });
+    }
+
+    #[test]
+    fn every_error_is_reported() {
+        let result = parse_expression_recovering("p & | (q");
+        assert_json!(result.as_str(), {
+            "ast": {
+                "type": "operator.or",
+                "left": {
+                    "type": "operator.and",
+                    "right": { "type": "error", "span": [4, 4] }
+                }
+            },
+            "errors": [
+                { "code": "E0201", "span": [4, 5] },
+                { "code": "E0202", "span": [8, 8], "fixes": [{ "replacement": ")", "span": [8, 8] }] }
+            ]
+        });
+    }
+
+    #[test]
+    fn errors_suggest_fixes() {
+        let result = parse_expression("p = q");
+        assert_json!(result.as_str(), {
+            "code": "E0101",
+            "expected": ["'=>'"],
+            "fixes": [{
+                "message": "replace '=' with '=>'",
+                "span": [2, 3],
+                "replacement": "=>",
+                "start": { "utf16_offset": 2 },
+                "end": { "utf16_offset": 3 }
+            }]
+        });
+    }
+
+    #[test]
+    fn errors_have_utf16_positions() {
+        let result = parse_expression("𝑝 ∧ q\n∧ $");
+        assert_json!(result.as_str(), {
+            "code": "E0102",
+            "span": [15, 16],
+            "start": { "line": 2, "column": 3, "utf16_column": 3, "offset": 15, "utf16_offset": 9 }
         });
     }
 }
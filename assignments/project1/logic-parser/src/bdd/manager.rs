@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+use crate::errors::TransformError;
+use crate::evaluating::Model;
+use crate::parsing::ASTNode;
+
+/// Reference to a node of a [`Bdd`].
+///
+/// Nodes are hash-consed, so two references of the same [`Bdd`] are equal if
+/// and only if they represent equivalent functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// A decision node: `if var then high else low`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BddNode {
+    /// Position of the variable in the order of the [`Bdd`]
+    pub var: usize,
+    pub low: NodeId,
+    pub high: NodeId
+}
+
+/// Binary boolean operations supported by [`Bdd::apply`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BddOperation {
+    And,
+    Or,
+    Xor,
+    Implies,
+    IfAndOnlyIf,
+    Nand,
+    Nor
+}
+
+impl BddOperation {
+    fn eval(&self, a: bool, b: bool) -> bool {
+        match self {
+            BddOperation::And => a && b,
+            BddOperation::Or => a || b,
+            BddOperation::Xor => a != b,
+            BddOperation::Implies => !a || b,
+            BddOperation::IfAndOnlyIf => a == b,
+            BddOperation::Nand => !(a && b),
+            BddOperation::Nor => !(a || b),
+        }
+    }
+}
+
+/// Manager of reduced ordered binary decision diagrams.
+///
+/// All the diagrams built by the same manager share their nodes and use the
+/// same variable order. Variables that are not in the order when first used
+/// are appended to it.
+///
+/// ```
+/// use logic_parser::bdd::Bdd;
+/// use logic_parser::lexing::Lexer;
+/// use logic_parser::parsing::Parser;
+///
+/// let parse = |e| Parser::new(&Lexer::new().tokenize(e).unwrap()).parse().unwrap();
+///
+/// let mut bdd = Bdd::with_order(&["p", "q"]);
+/// let a = bdd.from_ast(&parse("p -> q")).unwrap();
+/// let b = bdd.from_ast(&parse("~q -> ~p")).unwrap();
+///
+/// assert_eq!(a, b);
+/// assert_eq!(bdd.sat_count(a), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bdd {
+    order: Vec<String>,
+    nodes: Vec<BddNode>,
+    unique: HashMap<BddNode, NodeId>,
+    apply_cache: HashMap<(BddOperation, NodeId, NodeId), NodeId>
+}
+
+impl Bdd {
+    pub const FALSE: NodeId = NodeId(0);
+    pub const TRUE: NodeId = NodeId(1);
+
+    /// Creates a manager with an empty variable order.
+    pub fn new() -> Self {
+        Self::with_order::<&str>(&[])
+    }
+
+    /// Creates a manager whose variables are tested in the given order, the
+    /// first one being at the root.
+    pub fn with_order<S: AsRef<str>>(order: &[S]) -> Self {
+        // Terminals are stored as nodes with a variable past every level
+        let terminal = BddNode { var: usize::MAX, low: Self::FALSE, high: Self::FALSE };
+        Bdd {
+            order: order.iter().map(|s| s.as_ref().to_owned()).collect(),
+            nodes: vec![terminal, terminal],
+            unique: HashMap::new(),
+            apply_cache: HashMap::new()
+        }
+    }
+
+    pub fn order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// Returns the decision node, or `None` for the terminals.
+    pub fn node(&self, id: NodeId) -> Option<&BddNode> {
+        if self.is_terminal(id) { None } else { self.nodes.get(id.0) }
+    }
+
+    pub fn is_terminal(&self, id: NodeId) -> bool {
+        id == Self::FALSE || id == Self::TRUE
+    }
+
+    /// Name of the variable tested by a decision node.
+    pub fn var_name(&self, id: NodeId) -> Option<&str> {
+        self.node(id).map(|n| self.order[n.var].as_str())
+    }
+
+    /// Number of nodes reachable from `root`, terminals included.
+    pub fn size(&self, root: NodeId) -> usize {
+        self.reachable(root).len()
+    }
+
+    /// Nodes reachable from `root` in breadth first order.
+    pub fn reachable(&self, root: NodeId) -> Vec<NodeId> {
+        let mut visited = vec![root];
+        let mut i = 0;
+        while i < visited.len() {
+            if let Some(node) = self.node(visited[i]) {
+                for child in [node.low, node.high] {
+                    if !visited.contains(&child) {
+                        visited.push(child);
+                    }
+                }
+            }
+            i += 1;
+        }
+        visited
+    }
+
+    /// The diagram of a single variable.
+    pub fn variable(&mut self, name: &str) -> NodeId {
+        let var = match self.order.iter().position(|v| v == name) {
+            Some(var) => var,
+            None => {
+                self.order.push(name.to_owned());
+                self.order.len() - 1
+            }
+        };
+        self.make_node(var, Self::FALSE, Self::TRUE)
+    }
+
+    pub fn constant(&self, value: bool) -> NodeId {
+        if value { Self::TRUE } else { Self::FALSE }
+    }
+
+    /// Compiles an expression. Fails if it has error nodes.
+    pub fn from_ast(&mut self, ast: &ASTNode) -> Result<NodeId, TransformError> {
+        let f = match ast {
+            ASTNode::Identifier { name, .. } => self.variable(name),
+            ASTNode::Error { span } => return Err(TransformError::ErrorNode(*span)),
+            ASTNode::Literal { value, .. } => self.constant(*value),
+            ASTNode::Not { operand, .. } => {
+                let f = self.from_ast(operand)?;
+                self.not(f)
+            },
+            ASTNode::And { left, right, .. } => self.apply_ast(BddOperation::And, left, right)?,
+            ASTNode::Or { left, right, .. } => self.apply_ast(BddOperation::Or, left, right)?,
+            ASTNode::Implies { left, right, .. } => self.apply_ast(BddOperation::Implies, left, right)?,
+            ASTNode::IfAndOnlyIf { left, right, .. } => self.apply_ast(BddOperation::IfAndOnlyIf, left, right)?,
+            ASTNode::Xor { left, right, .. } => self.apply_ast(BddOperation::Xor, left, right)?,
+            ASTNode::Nand { left, right, .. } => self.apply_ast(BddOperation::Nand, left, right)?,
+            ASTNode::Nor { left, right, .. } => self.apply_ast(BddOperation::Nor, left, right)?,
+        };
+        Ok(f)
+    }
+
+    fn apply_ast(&mut self, operation: BddOperation, left: &ASTNode, right: &ASTNode) -> Result<NodeId, TransformError> {
+        let (f, g) = (self.from_ast(left)?, self.from_ast(right)?);
+        Ok(self.apply(operation, f, g))
+    }
+
+    pub fn not(&mut self, f: NodeId) -> NodeId {
+        self.apply(BddOperation::Xor, f, Self::TRUE)
+    }
+
+    pub fn and(&mut self, f: NodeId, g: NodeId) -> NodeId {
+        self.apply(BddOperation::And, f, g)
+    }
+
+    pub fn or(&mut self, f: NodeId, g: NodeId) -> NodeId {
+        self.apply(BddOperation::Or, f, g)
+    }
+
+    pub fn xor(&mut self, f: NodeId, g: NodeId) -> NodeId {
+        self.apply(BddOperation::Xor, f, g)
+    }
+
+    /// Combines two diagrams with a binary operation.
+    pub fn apply(&mut self, operation: BddOperation, f: NodeId, g: NodeId) -> NodeId {
+        if self.is_terminal(f) && self.is_terminal(g) {
+            return self.constant(operation.eval(f == Self::TRUE, g == Self::TRUE));
+        }
+        if let Some(result) = self.apply_cache.get(&(operation, f, g)) {
+            return *result;
+        }
+
+        let (vf, vg) = (self.nodes[f.0].var, self.nodes[g.0].var);
+        let var = vf.min(vg);
+        let (f_low, f_high) = self.cofactors(f, var);
+        let (g_low, g_high) = self.cofactors(g, var);
+
+        let low = self.apply(operation, f_low, g_low);
+        let high = self.apply(operation, f_high, g_high);
+        let result = self.make_node(var, low, high);
+
+        self.apply_cache.insert((operation, f, g), result);
+        result
+    }
+
+    /// Fixes the value of a variable. Returns `f` unchanged if the variable is
+    /// unknown.
+    pub fn restrict(&mut self, f: NodeId, var: &str, value: bool) -> NodeId {
+        match self.order.iter().position(|v| v == var) {
+            Some(var) => self.restrict_level(f, var, value, &mut HashMap::new()),
+            None => f
+        }
+    }
+
+    fn restrict_level(&mut self, f: NodeId, var: usize, value: bool, cache: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        let node = match self.node(f) {
+            Some(node) if node.var <= var => *node,
+            _ => return f
+        };
+        if node.var == var {
+            return if value { node.high } else { node.low };
+        }
+        if let Some(result) = cache.get(&f) {
+            return *result;
+        }
+        let low = self.restrict_level(node.low, var, value, cache);
+        let high = self.restrict_level(node.high, var, value, cache);
+        let result = self.make_node(node.var, low, high);
+        cache.insert(f, result);
+        result
+    }
+
+    /// Existential quantification: `∃var. f`.
+    pub fn exists(&mut self, f: NodeId, var: &str) -> NodeId {
+        let (low, high) = (self.restrict(f, var, false), self.restrict(f, var, true));
+        self.or(low, high)
+    }
+
+    /// Universal quantification: `∀var. f`.
+    pub fn forall(&mut self, f: NodeId, var: &str) -> NodeId {
+        let (low, high) = (self.restrict(f, var, false), self.restrict(f, var, true));
+        self.and(low, high)
+    }
+
+    /// Whether two diagrams are equivalent. This is a constant time check.
+    pub fn equivalent(&self, f: NodeId, g: NodeId) -> bool {
+        f == g
+    }
+
+    /// Number of assignments of all the variables of the order that satisfy
+    /// `f`.
+    ///
+    /// # Panics
+    ///
+    /// If the order has more than 127 variables.
+    pub fn sat_count(&self, f: NodeId) -> u128 {
+        assert!(self.order.len() < 128, "satisfying assignments are counted up to 127 variables");
+        let mut cache = HashMap::new();
+        self.count(f, &mut cache) << self.level(f)
+    }
+
+    /// Satisfying assignments of the variables from the level of `f` onwards.
+    fn count(&self, f: NodeId, cache: &mut HashMap<NodeId, u128>) -> u128 {
+        if f == Self::FALSE { return 0 }
+        if f == Self::TRUE { return 1 }
+        if let Some(count) = cache.get(&f) {
+            return *count;
+        }
+        let node = self.nodes[f.0];
+        let low = self.count(node.low, cache) << (self.level(node.low) - node.var - 1);
+        let high = self.count(node.high, cache) << (self.level(node.high) - node.var - 1);
+        cache.insert(f, low + high);
+        low + high
+    }
+
+    /// Returns a satisfying assignment of all the variables of the order, or
+    /// `None` if `f` is unsatisfiable. Variables not tested on the way are
+    /// set to `false`.
+    pub fn pick_model(&self, f: NodeId) -> Option<Model> {
+        if f == Self::FALSE {
+            return None;
+        }
+        let mut model: Model = self.order.iter().map(|v| (v.clone(), false)).collect();
+        let mut current = f;
+        while let Some(node) = self.node(current) {
+            let value = node.high != Self::FALSE;
+            model.insert(self.order[node.var].clone(), value);
+            current = if value { node.high } else { node.low };
+        }
+        Some(model)
+    }
+
+    fn level(&self, f: NodeId) -> usize {
+        self.node(f).map(|n| n.var).unwrap_or(self.order.len())
+    }
+
+    fn cofactors(&self, f: NodeId, var: usize) -> (NodeId, NodeId) {
+        match self.node(f) {
+            Some(node) if node.var == var => (node.low, node.high),
+            _ => (f, f)
+        }
+    }
+
+    fn make_node(&mut self, var: usize, low: NodeId, high: NodeId) -> NodeId {
+        if low == high {
+            return low;
+        }
+        let node = BddNode { var, low, high };
+        if let Some(id) = self.unique.get(&node) {
+            return *id;
+        }
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(node);
+        self.unique.insert(node, id);
+        id
+    }
+}
+
+impl Default for Bdd {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ASTNode {
+    /// Compiles the expression into a [`Bdd`] that tests the identifiers in
+    /// order of first appearance.
+    pub fn to_bdd(&self) -> Result<(Bdd, NodeId), TransformError> {
+        let mut bdd = Bdd::with_order(&self.get_ordered_identifiers());
+        let root = bdd.from_ast(self)?;
+        Ok((bdd, root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::testing::{parse, parse_recovered};
+
+    #[test]
+    fn equivalent_expressions_share_the_root() {
+        let mut bdd = Bdd::new();
+        let a = bdd.from_ast(&parse("~(p & q) | r")).unwrap();
+        let b = bdd.from_ast(&parse("(p -> (q -> r))")).unwrap();
+        let c = bdd.from_ast(&parse("p -> r")).unwrap();
+
+        assert!(bdd.equivalent(a, b));
+        assert!(!bdd.equivalent(a, c));
+        assert_eq!(bdd.from_ast(&parse("p | ~p")).unwrap(), Bdd::TRUE);
+        assert_eq!(bdd.from_ast(&parse("(p <-> q) & (p <-> ~q)")).unwrap(), Bdd::FALSE);
+    }
+
+    #[test]
+    fn diagrams_are_reduced() {
+        // (a1 <-> b1) & (a2 <-> b2) has 3 * 2 + 2 nodes with a1 b1 a2 b2
+        let ast = parse("(a1 <-> b1) & (a2 <-> b2)");
+        let (bdd, root) = ast.to_bdd().unwrap();
+        assert_eq!(bdd.size(root), 8);
+
+        // but grows exponentially with a1 a2 b1 b2
+        let mut bdd = Bdd::with_order(&["a1", "a2", "b1", "b2"]);
+        let root = bdd.from_ast(&ast).unwrap();
+        assert_eq!(bdd.size(root), 11);
+    }
+
+    #[test]
+    fn satisfying_assignments_are_counted() {
+        let (bdd, root) = parse("p | q").to_bdd().unwrap();
+        assert_eq!(bdd.sat_count(root), 3);
+
+        let mut bdd = Bdd::with_order(&["x", "p", "y", "q", "z"]);
+        let root = bdd.from_ast(&parse("p <-> q")).unwrap();
+        assert_eq!(bdd.sat_count(root), 16);
+        assert_eq!(bdd.sat_count(Bdd::TRUE), 32);
+        assert_eq!(bdd.sat_count(Bdd::FALSE), 0);
+    }
+
+    #[test]
+    fn restriction_and_quantification() {
+        let (mut bdd, root) = parse("(p & q) | r").to_bdd().unwrap();
+
+        let r_false = bdd.restrict(root, "r", false);
+        let p_and_q = bdd.from_ast(&parse("p & q")).unwrap();
+        assert_eq!(r_false, p_and_q);
+
+        let exists_p = bdd.exists(root, "p");
+        let q_or_r = bdd.from_ast(&parse("q | r")).unwrap();
+        assert_eq!(exists_p, q_or_r);
+
+        let forall_p = bdd.forall(root, "p");
+        let r = bdd.variable("r");
+        assert_eq!(forall_p, r);
+
+        let q = bdd.variable("q");
+        let xor = bdd.xor(root, q);
+        assert_eq!(bdd.sat_count(xor), 3);
+    }
+
+    #[test]
+    fn picked_models_satisfy_the_expression() {
+        let ast = parse("(a -> b) & (b -> c) & a");
+        let (bdd, root) = ast.to_bdd().unwrap();
+        let model = bdd.pick_model(root).unwrap();
+        assert!(ast.evaluate(&model).unwrap());
+        assert!(bdd.pick_model(Bdd::FALSE).is_none());
+    }
+
+    #[test]
+    fn error_nodes_result_on_error() {
+        let ast = parse_recovered("(p | $) & q");
+        assert!(matches!(ast.to_bdd(), Err(TransformError::ErrorNode(Some(_)))));
+        assert!(matches!(Bdd::new().from_ast(&ast), Err(TransformError::ErrorNode(Some(_)))));
+    }
+}
@@ -0,0 +1,493 @@
+use crate::errors::{LexerError, ParserError};
+use crate::lexing::token::{Span, TokenKind};
+use crate::parsing::Expected;
+use super::position::{Position, SourceMap};
+use super::fix::Fix;
+
+/// Errors that point at a range of the source code.
+///
+/// Every error has a stable code that won't change between versions, so
+/// frontends can match on it instead of on the message:
+///
+/// | Code    | Error                                   |
+/// |---------|-----------------------------------------|
+/// | `E0101` | [`LexerError::SyntaxError`], [`LexerError::IncompleteSymbol`] |
+/// | `E0102` | [`LexerError::UnknownToken`]            |
+/// | `E0103` | [`LexerError::InvalidAlphabet`]         |
+/// | `E0104` | [`LexerError::UnterminatedString`]      |
+/// | `E0105` | [`LexerError::UnterminatedComment`]     |
+/// | `E0201` | [`ParserError::UnexpectedToken`]        |
+/// | `E0202` | [`ParserError::UnexpectedEOF`]          |
+///
+/// [`ParserError::LexingError`] uses the code of the wrapped error.
+pub trait SourceError: std::fmt::Display {
+    fn code(&self) -> &'static str;
+    fn span(&self) -> Span;
+    fn message(&self) -> String {
+        self.to_string()
+    }
+    /// Hint on how to fix the error
+    fn help(&self) -> Option<String> {
+        None
+    }
+    /// What would have been accepted at the span, like `'&'` or `identifier`
+    fn expected(&self) -> Vec<String> {
+        vec![]
+    }
+    /// Edits of the source that fix the error
+    fn fixes(&self, _map: &SourceMap) -> Vec<Fix> {
+        vec![]
+    }
+}
+
+impl SourceError for LexerError {
+    fn code(&self) -> &'static str {
+        match self {
+            LexerError::SyntaxError(..) |
+            LexerError::IncompleteSymbol(..) => "E0101",
+            LexerError::UnknownToken(..) => "E0102",
+            LexerError::InvalidAlphabet(..) => "E0103",
+            LexerError::UnterminatedString(..) => "E0104",
+            LexerError::UnterminatedComment(..) => "E0105"
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            LexerError::SyntaxError(_, span) |
+            LexerError::IncompleteSymbol(_, span) |
+            LexerError::UnknownToken(_, span) |
+            LexerError::InvalidAlphabet(_, span) |
+            LexerError::UnterminatedString(_, span) |
+            LexerError::UnterminatedComment(_, span) => *span
+        }
+    }
+
+    fn help(&self) -> Option<String> {
+        match self {
+            LexerError::SyntaxError(..) |
+            LexerError::IncompleteSymbol(..) => None,
+            LexerError::UnknownToken(c, _) => {
+                Some(format!("'{c}' is not an operator, quote the identifier if it is part of a name"))
+            },
+            LexerError::InvalidAlphabet(c, _) => {
+                Some(format!("add '{c}' to the alphabet of the identifiers"))
+            },
+            LexerError::UnterminatedString(quote, _) => Some(format!("add a closing {quote}")),
+            LexerError::UnterminatedComment(end, _) => Some(format!("close the comment with {end}"))
+        }
+    }
+
+    fn expected(&self) -> Vec<String> {
+        match self {
+            LexerError::IncompleteSymbol(symbols, _) => symbols.iter().map(|s| format!("'{s}'")).collect(),
+            _ => vec![]
+        }
+    }
+
+    /// Incomplete symbols are replaced by the symbols that start with the
+    /// longest part of them that was typed, so `<-` becomes `<->`.
+    fn fixes(&self, map: &SourceMap) -> Vec<Fix> {
+        let LexerError::IncompleteSymbol(symbols, span) = self else {
+            return vec![]
+        };
+        let rest = map.source().get(span.start..).unwrap_or_default();
+        let typed = |symbol: &str| -> usize {
+            symbol.char_indices().zip(rest.chars())
+                .take_while(|((_, a), b)| a == b)
+                .map(|((i, a), _)| i + a.len_utf8())
+                .last()
+                .unwrap_or(0)
+        };
+        let longest = symbols.iter().map(|s| typed(s)).max().unwrap_or(0);
+
+        symbols.iter()
+            .filter(|s| typed(s) == longest)
+            .map(|s| Fix::replace(map, (span.start, span.start + longest).into(), s))
+            .collect()
+    }
+}
+
+impl SourceError for ParserError {
+    fn code(&self) -> &'static str {
+        match self {
+            ParserError::UnexpectedToken(..) => "E0201",
+            ParserError::UnexpectedEOF(..) => "E0202",
+            ParserError::LexingError(error) => error.code()
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            ParserError::UnexpectedToken(_, span, _) |
+            ParserError::UnexpectedEOF(_, span, _) => *span,
+            ParserError::LexingError(error) => error.span()
+        }
+    }
+
+    /// Errors of the lexer are reported without the `Lexing error:` prefix
+    fn message(&self) -> String {
+        match self {
+            ParserError::LexingError(error) => error.message(),
+            other => other.to_string()
+        }
+    }
+
+    fn help(&self) -> Option<String> {
+        match self {
+            ParserError::UnexpectedToken(..) => None,
+            ParserError::UnexpectedEOF(..) => {
+                Some("the expression is incomplete, add an identifier, a literal or a parenthesised expression".into())
+            },
+            ParserError::LexingError(error) => error.help()
+        }
+    }
+
+    fn expected(&self) -> Vec<String> {
+        match self {
+            ParserError::LexingError(error) => error.expected(),
+            other => other.expected().iter().map(Expected::to_string).collect()
+        }
+    }
+
+    /// Missing closing parentheses are inserted where the parser expected
+    /// them, unless the parenthesis is closed later on.
+    fn fixes(&self, map: &SourceMap) -> Vec<Fix> {
+        let close = Expected::Token(TokenKind::CloseParen);
+        match self {
+            ParserError::UnexpectedToken(_, span, expected)
+            if expected.contains(&close) && !is_closed_later(map.source(), span.start) => {
+                vec![Fix::insert(map, span.start, ")")]
+            },
+            ParserError::UnexpectedEOF(_, span, expected) if expected.contains(&close) => {
+                vec![Fix::insert(map, span.end, ")")]
+            },
+            ParserError::LexingError(error) => error.fixes(map),
+            _ => vec![]
+        }
+    }
+}
+
+/// A [`SourceError`] located in its source code.
+///
+/// ```
+/// use logic_parser::lexing::Lexer;
+/// use logic_parser::parsing::Parser;
+/// use logic_parser::diagnostics::Diagnostic;
+///
+/// let source = "p &\n  (q | r s";
+/// let tokens = Lexer::new().tokenize(source).unwrap();
+/// let error = Parser::new(&tokens).parse().unwrap_err();
+///
+/// let diagnostic = Diagnostic::new(&error, source);
+/// assert_eq!(diagnostic.code, "E0201");
+/// assert_eq!(diagnostic.render(source), "\
+/// error[E0201]: Unexpected token: R_PAREN expected
+///  --> 2:10
+///   |
+/// 2 |   (q | r s
+///   |          ^
+///   = expected '&', 'nand', '|', '^', 'nor', '->', '<->' or ')'
+///   = fix: insert ')'
+/// ");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    pub code: String,
+    pub message: String,
+    pub help: Option<String>,
+    pub span: Span,
+    pub start: Position,
+    pub end: Position,
+    /// What would have been accepted at the span, see [`SourceError::expected`]
+    pub expected: Vec<String>,
+    pub fixes: Vec<Fix>
+}
+
+impl Diagnostic {
+    pub fn new<E: SourceError>(error: &E, source: &str) -> Self {
+        let map = SourceMap::new(source);
+        let span = error.span();
+        Diagnostic {
+            code: error.code().to_owned(),
+            message: error.message(),
+            help: error.help(),
+            span,
+            start: map.position(span.start),
+            end: map.position(span.end),
+            expected: error.expected(),
+            fixes: error.fixes(&map)
+        }
+    }
+
+    /// Formats the diagnostic for a terminal, underlining the span in the
+    /// lines of `source` it covers.
+    ///
+    /// Empty spans, like the ones at the end of the input, get a single
+    /// caret. Tabs are expanded to four spaces, and wide characters get as
+    /// many carets as the columns they take.
+    pub fn render(&self, source: &str) -> String {
+        let map = SourceMap::new(source);
+        // A span that ends at the start of a line doesn't cover it
+        let last_line = if self.end.line > self.start.line && self.end.column == 1 {
+            self.end.line - 1
+        } else {
+            self.end.line
+        };
+        let gutter = " ".repeat(last_line.to_string().len());
+
+        let mut output = format!("error[{}]: {}\n", self.code, self.message);
+        output += &format!("{gutter}--> {}\n", self.start);
+        output += &format!("{gutter} |\n");
+
+        for line in self.start.line..=last_line {
+            let text = map.line(line).unwrap_or_default();
+            let from = if line == self.start.line { self.start.column - 1 } else { 0 };
+            let to = if line == self.end.line { self.end.column - 1 } else { text.chars().count() };
+
+            let indent = " ".repeat(text.chars().take(from).map(display_width).sum());
+            let underlined: usize = text.chars().skip(from).take(to.saturating_sub(from)).map(display_width).sum();
+            let carets = "^".repeat(underlined.max(1));
+            let text = text.replace('\t', TAB);
+
+            output += &format!("{line:>width$} | {text}\n", width = gutter.len());
+            output += &format!("{gutter} | {indent}{carets}\n");
+        }
+
+        if let Some((last, rest)) = self.expected.split_last() {
+            let expected = if rest.is_empty() { last.clone() } else { format!("{} or {last}", rest.join(", ")) };
+            output += &format!("{gutter} = expected {expected}\n");
+        }
+        if let Some(help) = &self.help {
+            output += &format!("{gutter} = help: {help}\n");
+        }
+        for fix in &self.fixes {
+            output += &format!("{gutter} = fix: {}\n", fix.message);
+        }
+        output
+    }
+
+    #[cfg(not(feature = "serde"))]
+    pub fn as_json(&self) -> String {
+        let position = |p: &Position| format!(
+            r###"{{ "line": {}, "column": {}, "utf16_column": {}, "offset": {}, "utf16_offset": {} }}"###,
+            p.line, p.column, p.utf16_column, p.offset, p.utf16_offset
+        );
+        format!(r###"{{
+            "code": "{code}",
+            "message": {message},
+            "help": {help},
+            "span": [{span_start}, {span_end}],
+            "start": {start},
+            "end": {end},
+            "expected": [{expected}],
+            "fixes": [{fixes}]
+        }}"###,
+            code = self.code,
+            message = json_string(&self.message),
+            help = self.help.as_deref().map(json_string).unwrap_or("null".into()),
+            span_start = self.span.start,
+            span_end = self.span.end,
+            start = position(&self.start),
+            end = position(&self.end),
+            expected = self.expected.iter().map(|e| json_string(e)).collect::<Vec<String>>().join(", "),
+            fixes = self.fixes.iter().map(|fix| format!(
+                r###"{{ "message": {}, "span": [{}, {}], "replacement": {}, "start": {}, "end": {} }}"###,
+                json_string(&fix.message), fix.span.start, fix.span.end, json_string(&fix.replacement),
+                position(&fix.start), position(&fix.end)
+            )).collect::<Vec<String>>().join(", ")
+        )
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn as_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error[{}] at {}: {}", self.code, self.start, self.message)
+    }
+}
+
+/// Whether a `)` after `offset` closes the parenthesis open there. The ones
+/// inside quoted identifiers are skipped.
+fn is_closed_later(source: &str, offset: usize) -> bool {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for c in source.get(offset..).unwrap_or_default().chars() {
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {},
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => return true,
+            (None, ')') => depth -= 1,
+            _ => {}
+        }
+    }
+    false
+}
+
+const TAB: &str = "    ";
+
+/// Columns taken by `c` in a terminal.
+fn display_width(c: char) -> usize {
+    if c == '\t' { TAB.len() } else { unicode_width::UnicodeWidthChar::width(c).unwrap_or(0) }
+}
+
+/// Quotes and escapes `text` as a JSON string.
+#[cfg(not(feature = "serde"))]
+pub(crate) fn json_string(text: &str) -> String {
+    let mut escaped = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c)
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexing::Lexer;
+    use crate::parsing::Parser;
+
+    fn diagnose(source: &str) -> Diagnostic {
+        let error = Lexer::new().tokenize(source).map_err(ParserError::from)
+            .and_then(|tokens| Parser::new(&tokens).parse().map(|_| ()))
+            .unwrap_err();
+        Diagnostic::new(&error, source)
+    }
+
+    #[test]
+    fn errors_have_stable_codes() {
+        let cases = [
+            ("p & $", "E0102"),
+            ("\"p", "E0104"),
+            ("p /* q", "E0105"),
+            ("p q", "E0201"),
+            ("p ->", "E0202"),
+        ];
+        for (source, code) in cases {
+            assert_eq!(diagnose(source).code, code, "{source}");
+        }
+    }
+
+    #[test]
+    fn spans_are_underlined() {
+        // '日' takes two columns and tabs four
+        let source = "a ∧ b ∧ \"日\td";
+        assert_eq!(diagnose(source).render(source), "\
+error[E0104]: Unterminated string: missing closing \"
+ --> 1:9
+  |
+1 | a ∧ b ∧ \"日    d
+  |         ^^^^^^^^
+  = help: add a closing \"
+");
+    }
+
+    #[test]
+    fn multiline_spans_underline_every_line() {
+        let source = "p |\n\t/* never\n closed";
+        assert_eq!(diagnose(source).render(source), "\
+error[E0105]: Unterminated comment: missing closing */
+ --> 2:2
+  |
+2 |     /* never
+  |     ^^^^^^^^
+3 |  closed
+  | ^^^^^^^
+  = help: close the comment with */
+");
+    }
+
+    #[test]
+    fn end_of_input_gets_a_caret() {
+        let source = "p ->\n";
+        let diagnostic = diagnose(source);
+        assert_eq!(diagnostic.start.line, 1);
+        assert!(diagnostic.render(source).contains("1 | p ->\n  |   ^^\n"));
+    }
+
+    #[test]
+    fn fixes_can_be_applied() {
+        let cases = [
+            ("(p & q", "(p & q)"),
+            ("((p) -> q\n", "((p) -> q)\n"),
+            ("p = q", "p => q"),
+            ("p <- q", "p <-> q"),
+            ("p → (q <= r)", "p → (q <=> r)"),
+        ];
+        for (source, fixed) in cases {
+            let diagnostic = diagnose(source);
+            assert_eq!(diagnostic.fixes.len(), 1, "{source}");
+            assert_eq!(diagnostic.fixes[0].apply(source), fixed);
+        }
+
+        let fixes = diagnose("p < q").fixes;
+        let fixed: Vec<String> = fixes.iter().map(|fix| fix.apply("p < q")).collect();
+        assert_eq!(fixed, vec!["p <-> q", "p <=> q"]);
+
+        assert!(diagnose("p & & q").fixes.is_empty());
+        // The parenthesis is already closed, inserting another one is wrong
+        assert!(diagnose("(p q)").fixes.is_empty());
+        assert!(diagnose("((p q) & r)").fixes.is_empty());
+        assert_eq!(diagnose("(p q & ')'").fixes[0].apply("(p q & ')'"), "(p )q & ')'");
+    }
+
+    #[test]
+    fn expected_tokens_are_listed() {
+        let source = "p = q";
+        assert_eq!(diagnose(source).render(source), "\
+error[E0101]: Syntax error: expected one of the following: '=>'
+ --> 1:3
+  |
+1 | p = q
+  |   ^
+  = expected '=>'
+  = fix: replace '=' with '=>'
+");
+    }
+
+    #[test]
+    fn diagnostics_are_rendered_in_json() {
+        use assert_json::assert_json;
+        let source = "→ \"x";
+        let diagnostic = diagnose(source);
+
+        assert_json!(diagnostic.as_json().as_str(), {
+            "code": "E0104",
+            "message": "Unterminated string: missing closing \"",
+            "span": [4, 6],
+            "start": { "line": 1, "column": 3, "utf16_column": 3, "offset": 4, "utf16_offset": 2 },
+            "end": { "line": 1, "column": 5, "utf16_column": 5, "offset": 6, "utf16_offset": 4 },
+            "expected": [],
+            "fixes": []
+        });
+
+        let source = "¬(p ∧ q";
+        assert_json!(diagnose(source).as_json().as_str(), {
+            "code": "E0202",
+            "expected": ["'&'", "'nand'", "'|'", "'^'", "'nor'", "'->'", "'<->'", "')'"],
+            "fixes": [{
+                "message": "insert ')'",
+                "span": [10, 10],
+                "replacement": ")",
+                "start": { "line": 1, "column": 8, "utf16_column": 8, "offset": 10, "utf16_offset": 7 }
+            }]
+        });
+    }
+}
@@ -0,0 +1,62 @@
+use crate::lexing::token::Span;
+use super::position::{Position, SourceMap};
+
+/// Edit of the source code that fixes an error: the text in `span` is
+/// replaced by `replacement`, so insertions have an empty span.
+///
+/// The positions of the span are included so frontends can apply the fix
+/// to JavaScript strings through the UTF-16 offsets.
+///
+/// ```
+/// use logic_parser::lexing::Lexer;
+/// use logic_parser::parsing::Parser;
+/// use logic_parser::diagnostics::Diagnostic;
+///
+/// let source = "(p -> q";
+/// let tokens = Lexer::new().tokenize(source).unwrap();
+/// let error = Parser::new(&tokens).parse().unwrap_err();
+///
+/// let fix = &Diagnostic::new(&error, source).fixes[0];
+/// assert_eq!(fix.message, "insert ')'");
+/// assert_eq!(fix.apply(source), "(p -> q)");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fix {
+    /// What the fix does, like `insert ')'`
+    pub message: String,
+    pub span: Span,
+    pub replacement: String,
+    pub start: Position,
+    pub end: Position
+}
+
+impl Fix {
+    /// Inserts `text` at the byte `offset`.
+    pub fn insert(map: &SourceMap, offset: usize, text: &str) -> Self {
+        Self::new(map, format!("insert '{text}'"), (offset, offset).into(), text)
+    }
+
+    /// Replaces the text in `span` by `text`.
+    pub fn replace(map: &SourceMap, span: Span, text: &str) -> Self {
+        let (start, end) = (map.position(span.start), map.position(span.end));
+        let old = &map.source()[start.offset..end.offset];
+        Self::new(map, format!("replace '{old}' with '{text}'"), span, text)
+    }
+
+    fn new(map: &SourceMap, message: String, span: Span, replacement: &str) -> Self {
+        let (start, end) = (map.position(span.start), map.position(span.end));
+        Fix {
+            message,
+            span: (start.offset, end.offset).into(),
+            replacement: replacement.to_owned(),
+            start,
+            end
+        }
+    }
+
+    /// Applies the fix to the source it was created for.
+    pub fn apply(&self, source: &str) -> String {
+        format!("{}{}{}", &source[..self.span.start], self.replacement, &source[self.span.end..])
+    }
+}
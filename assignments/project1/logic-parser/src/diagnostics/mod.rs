@@ -0,0 +1,7 @@
+mod position;
+mod diagnostic;
+mod fix;
+
+pub use position::*;
+pub use diagnostic::*;
+pub use fix::*;
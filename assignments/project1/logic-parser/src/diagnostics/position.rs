@@ -0,0 +1,122 @@
+/// Location of a byte offset in the source code.
+///
+/// Lines and columns start at 1. Columns are counted in characters, and
+/// also in UTF-16 code units, which is how JavaScript indexes strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub utf16_column: usize,
+    /// Byte offset in the source
+    pub offset: usize,
+    /// Offset in UTF-16 code units from the start of the source
+    pub utf16_offset: usize
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Index of the lines of a source, to turn the byte offsets of a
+/// [`Span`](crate::lexing::token::Span) into [`Position`]s.
+///
+/// ```
+/// use logic_parser::diagnostics::SourceMap;
+///
+/// let source = "p ∧ q\n→ r";
+/// let map = SourceMap::new(source);
+///
+/// let position = map.position(source.find('r').unwrap());
+/// assert_eq!((position.line, position.column), (2, 3));
+/// assert_eq!(map.line(2), Some("→ r"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SourceMap<'a> {
+    source: &'a str,
+    /// Byte offset where each line starts
+    line_starts: Vec<usize>
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        SourceMap { source, line_starts }
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Text of the line `line` (starting at 1), without its line break.
+    pub fn line(&self, line: usize) -> Option<&'a str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self.line_starts.get(line).map(|next| next - 1).unwrap_or(self.source.len());
+        let text = &self.source[start..end];
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    /// Position of the byte `offset`. Offsets past the end of the source
+    /// are clamped to it, and offsets inside a character are moved to its
+    /// first byte.
+    pub fn position(&self, offset: usize) -> Position {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let before = &self.source[self.line_starts[line - 1]..offset];
+
+        Position {
+            line,
+            column: before.chars().count() + 1,
+            utf16_column: before.encode_utf16().count() + 1,
+            offset,
+            utf16_offset: self.source[..offset].encode_utf16().count()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_count_characters_and_utf16_units() {
+        let source = "a → 𝑏\n\n  c";
+        let map = SourceMap::new(source);
+
+        let b = map.position(source.find('𝑏').unwrap());
+        assert_eq!((b.line, b.column, b.utf16_column), (1, 5, 5));
+        assert_eq!((b.offset, b.utf16_offset), (6, 4));
+
+        // 𝑏 takes two UTF-16 code units
+        let end = map.position(source.find('\n').unwrap());
+        assert_eq!((end.column, end.utf16_column), (6, 7));
+
+        let c = map.position(source.len() - 1);
+        assert_eq!((c.line, c.column, c.utf16_column, c.utf16_offset), (3, 3, 3, 10));
+    }
+
+    #[test]
+    fn offsets_are_clamped() {
+        let source = "p → q\r\n";
+        let map = SourceMap::new(source);
+
+        // Inside the arrow
+        assert_eq!(map.position(3).offset, 2);
+        assert_eq!(map.position(100), Position { line: 2, column: 1, utf16_column: 1, offset: 9, utf16_offset: 7 });
+        assert_eq!(map.line(1), Some("p → q"));
+        assert_eq!(map.line(2), Some(""));
+        assert_eq!(map.line(3), None);
+    }
+}
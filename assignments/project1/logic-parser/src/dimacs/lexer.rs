@@ -0,0 +1,148 @@
+use crate::errors::LexerError;
+use crate::lexing::token::Span;
+
+type Result<T> = std::result::Result<T, LexerError>;
+
+/// Tokens of the DIMACS CNF format.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DimacsTokenKind {
+    /// A `c` line, without the leading `c`
+    Comment(String),
+    /// The `p` that starts the problem line
+    Problem,
+    /// Any other word, like the `cnf` format of the problem line
+    Word(String),
+    Integer(i64)
+}
+
+#[derive(Debug, Clone)]
+pub struct DimacsToken {
+    pub kind: DimacsTokenKind,
+    pub span: Span
+}
+
+/// Lexer for [DIMACS CNF](https://people.sc.fsu.edu/~jburkardt/data/cnf/cnf.html)
+/// files.
+///
+/// A `%` ends the input, as found in the SATLIB benchmark files.
+pub struct DimacsLexer<'a> {
+    src: &'a str,
+    pos: usize
+}
+
+impl<'a> DimacsLexer<'a> {
+    pub fn new() -> Self {
+        DimacsLexer { src: "", pos: 0 }
+    }
+
+    pub fn tokenize(&mut self, src: &'a str) -> Result<Vec<DimacsToken>> {
+        let mut tokens = Vec::new();
+        self.src = src;
+        self.pos = 0;
+
+        while let Some(token) = self.next_token()? {
+            tokens.push(token);
+        }
+        Ok(tokens)
+    }
+
+    fn next_token(&mut self) -> Result<Option<DimacsToken>> {
+        self.take_while(char::is_whitespace);
+        let start = self.pos;
+
+        let c = match self.peek_char() {
+            Some('%') | None => return Ok(None),
+            Some(c) => c
+        };
+
+        let kind = match c {
+            '-' | '0'..='9' => {
+                self.pos += 1;
+                self.take_while(|c| c.is_ascii_digit());
+                let text = &self.src[start..self.pos];
+                let value = text.parse::<i64>().map_err(|_| {
+                    LexerError::SyntaxError(format!("invalid integer '{text}'"), (start, self.pos).into())
+                })?;
+                DimacsTokenKind::Integer(value)
+            },
+            c if c.is_alphabetic() => {
+                self.take_while(|c| !c.is_whitespace());
+                match &self.src[start..self.pos] {
+                    "c" => {
+                        let from = self.pos;
+                        self.take_while(|c| c != '\n');
+                        DimacsTokenKind::Comment(self.src[from..self.pos].trim().to_owned())
+                    },
+                    "p" => DimacsTokenKind::Problem,
+                    word => DimacsTokenKind::Word(word.to_owned())
+                }
+            },
+            _ => {
+                return Err(
+                    LexerError::UnknownToken(c, (start, start + c.len_utf8()).into())
+                )
+            }
+        };
+
+        Ok(Some(DimacsToken { kind, span: (start, self.pos).into() }))
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn take_while<F>(&mut self, pred: F) -> usize
+    where F: Fn(char) -> bool {
+        let from = self.pos;
+
+        for c in self.src[self.pos..].chars() {
+            if !pred(c) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+
+        self.pos - from
+    }
+}
+
+impl<'a> Default for DimacsLexer<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dimacs_tokens_are_recognized() {
+        let tokens = DimacsLexer::new().tokenize("c hello world\np cnf 2 1\n-1 2 0\n%\n0\n").unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| &t.kind).collect::<Vec<_>>(),
+            vec![
+                &DimacsTokenKind::Comment("hello world".into()),
+                &DimacsTokenKind::Problem,
+                &DimacsTokenKind::Word("cnf".into()),
+                &DimacsTokenKind::Integer(2),
+                &DimacsTokenKind::Integer(1),
+                &DimacsTokenKind::Integer(-1),
+                &DimacsTokenKind::Integer(2),
+                &DimacsTokenKind::Integer(0),
+            ]
+        );
+        assert_eq!(tokens[3].span, (20, 21).into());
+    }
+
+    #[test]
+    fn unknown_characters_result_on_error() {
+        match DimacsLexer::new().tokenize("p cnf 1 1\n1 & 0").unwrap_err() {
+            LexerError::UnknownToken(c, span) => {
+                assert_eq!(c, '&');
+                assert_eq!(span, (12, 13).into());
+            },
+            _ => unreachable!()
+        }
+    }
+}
@@ -0,0 +1,6 @@
+mod lexer;
+mod parser;
+mod writer;
+
+pub use lexer::*;
+pub use parser::*;
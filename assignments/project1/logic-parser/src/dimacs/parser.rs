@@ -0,0 +1,237 @@
+use crate::errors::ParserError;
+use crate::lexing::token::Span;
+use crate::parsing::ASTNode;
+use crate::transforming::{Cnf, Literal};
+use super::lexer::{DimacsToken, DimacsTokenKind, DimacsLexer};
+use ParserError::{UnexpectedToken, UnexpectedEOF};
+
+pub type Result<T> = std::result::Result<T, ParserError>;
+
+/// Parser for the tokens produced by the [`DimacsLexer`].
+///
+/// ```yaml
+/// file: comment* "p" "cnf" vars clauses (clause | comment)*
+/// clause: (integer != 0)* 0
+/// ```
+///
+/// Comments of the form `c var <n> = <name>`, as written by
+/// [`Cnf::to_dimacs`], give names to the variables. Variables without a name
+/// are called `x<n>`. Two variables with the same name, given or generated,
+/// are an error. The last clause may omit its terminating `0`.
+#[derive(Debug)]
+pub struct DimacsParser<'a> {
+    tokens: &'a Vec<DimacsToken>,
+    /// Variable number, name and span of the naming comment
+    names: Vec<(i64, String, Span)>,
+    pos: usize
+}
+
+impl DimacsParser<'_> {
+    pub fn new(tokens: &Vec<DimacsToken>) -> DimacsParser<'_> {
+        DimacsParser { tokens, names: vec![], pos: 0 }
+    }
+
+    pub fn parse(&mut self) -> Result<Cnf> {
+        let (num_vars, num_clauses) = self.parse_problem_line()?;
+
+        let mut clauses: Vec<Vec<i64>> = Vec::new();
+        let mut clause: Vec<i64> = Vec::new();
+        while let Some(token) = self.next_significant() {
+            match token.kind {
+                DimacsTokenKind::Integer(0) => clauses.push(std::mem::take(&mut clause)),
+                DimacsTokenKind::Integer(l) if l.unsigned_abs() <= num_vars => clause.push(l),
+                DimacsTokenKind::Integer(l) => {
+                    return Err(UnexpectedToken(
+                        format!("variable {l} is greater than the declared amount ({num_vars})"),
+                        token.span,
+                        vec![]
+                    ))
+                },
+                ref other => {
+                    return Err(UnexpectedToken(format!("{other:?}, expected a literal"), token.span, vec![]))
+                }
+            }
+            if clauses.len() as u64 > num_clauses {
+                return Err(UnexpectedToken(
+                    format!("more clauses than the declared amount ({num_clauses})"),
+                    token.span,
+                    vec![]
+                ))
+            }
+        }
+        if !clause.is_empty() {
+            clauses.push(clause);
+        }
+        if (clauses.len() as u64) < num_clauses {
+            return Err(UnexpectedEOF(
+                format!("expected {num_clauses} clauses, found {}", clauses.len()),
+                self.last_span(),
+                vec![]
+            ))
+        }
+
+        let names = self.variable_names(&clauses)?;
+        let name = |v: i64| names.iter().find(|(n, _)| *n == v).map(|(_, name)| name.clone()).unwrap();
+        Ok(Cnf {
+            clauses: clauses.into_iter().map(|clause| {
+                clause.into_iter().map(|l| Literal::new(name(l.abs()), l < 0)).collect()
+            }).collect()
+        })
+    }
+
+    fn parse_problem_line(&mut self) -> Result<(u64, u64)> {
+        match self.next_significant() {
+            Some(DimacsToken { kind: DimacsTokenKind::Problem, .. }) => {},
+            Some(t) => return Err(UnexpectedToken(format!("{:?}, expected 'p cnf'", t.kind), t.span, vec![])),
+            None => return Err(UnexpectedEOF("expected 'p cnf'".into(), self.last_span(), vec![]))
+        }
+        match self.next_significant() {
+            Some(DimacsToken { kind: DimacsTokenKind::Word(w), .. }) if w == "cnf" => {},
+            Some(t) => return Err(UnexpectedToken(format!("{:?}, only the 'cnf' format is supported", t.kind), t.span, vec![])),
+            None => return Err(UnexpectedEOF("expected 'cnf'".into(), self.last_span(), vec![]))
+        }
+        let mut count = || match self.next_significant() {
+            Some(DimacsToken { kind: DimacsTokenKind::Integer(n), .. }) if n >= 0 => Ok(n as u64),
+            Some(t) => Err(UnexpectedToken(format!("{:?}, expected a positive integer", t.kind), t.span, vec![])),
+            None => Err(UnexpectedEOF("expected a positive integer".into(), self.last_span(), vec![]))
+        };
+        Ok((count()?, count()?))
+    }
+
+    /// Names of the variables used in `clauses`, the last comment naming a
+    /// variable wins.
+    fn variable_names(&self, clauses: &[Vec<i64>]) -> Result<Vec<(i64, String)>> {
+        let mut used: Vec<i64> = clauses.iter().flatten().map(|l| l.abs()).collect();
+        used.sort_unstable();
+        used.dedup();
+
+        let mut names: Vec<(i64, String)> = Vec::with_capacity(used.len());
+        for v in used {
+            let named = self.names.iter().rev().find(|(n, _, _)| *n == v);
+            let name = named.map(|(_, name, _)| name.clone()).unwrap_or_else(|| format!("x{v}"));
+
+            if let Some((other, _)) = names.iter().find(|(_, n)| *n == name) {
+                // Reported at the comment that gave the name, if any
+                let span = named.or_else(|| self.names.iter().rev().find(|(n, _, _)| n == other))
+                    .map(|(_, _, span)| *span)
+                    .unwrap_or_else(|| self.last_span());
+                return Err(UnexpectedToken(
+                    format!("variables {other} and {v} are both named '{name}'"),
+                    span,
+                    vec![]
+                ))
+            }
+            names.push((v, name));
+        }
+        Ok(names)
+    }
+
+    /// Consumes the next token that is not a comment, collecting the variable
+    /// names found along the way.
+    fn next_significant(&mut self) -> Option<DimacsToken> {
+        while let Some(token) = self.tokens.get(self.pos).cloned() {
+            self.pos += 1;
+            match token.kind {
+                DimacsTokenKind::Comment(comment) => {
+                    if let Some((number, name)) = parse_name_comment(&comment) {
+                        self.names.push((number, name, token.span));
+                    }
+                },
+                _ => return Some(token)
+            }
+        }
+        None
+    }
+
+    fn last_span(&self) -> Span {
+        self.tokens.last().map(|t| t.span).unwrap_or((0, 0).into())
+    }
+}
+
+/// Parses `var <n> = <name>`
+fn parse_name_comment(comment: &str) -> Option<(i64, String)> {
+    let rest = comment.strip_prefix("var")?;
+    let (number, name) = rest.split_once('=')?;
+    let number = number.trim().parse::<i64>().ok()?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| (number, name.to_owned()))
+}
+
+impl Cnf {
+    /// Reads a DIMACS CNF file.
+    ///
+    /// ```
+    /// use logic_parser::transforming::Cnf;
+    ///
+    /// let cnf = Cnf::from_dimacs("c var 1 = p\np cnf 2 2\n1 -2 0\n2 0\n").unwrap();
+    /// assert_eq!(cnf.to_ast().to_string(), "(p | ~x2) & x2");
+    /// ```
+    pub fn from_dimacs(src: &str) -> Result<Cnf> {
+        let tokens = DimacsLexer::new().tokenize(src)?;
+        DimacsParser::new(&tokens).parse()
+    }
+}
+
+impl ASTNode {
+    /// Reads a DIMACS CNF file as a conjunction of clauses. See
+    /// [`Cnf::from_dimacs`].
+    pub fn from_dimacs(src: &str) -> Result<ASTNode> {
+        Ok(Cnf::from_dimacs(src)?.to_ast())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_recovered_from_comments() {
+        let src = "c var 2 = tag:hot pink\nc var 1 = p\np cnf 3 2\n1 -2 0\n-3 2 0\n";
+        let ast = ASTNode::from_dimacs(src).unwrap();
+        assert_eq!(ast.get_ordered_identifiers(), vec!["p", "tag:hot pink", "x3"]);
+    }
+
+    #[test]
+    fn last_clause_may_omit_the_terminator() {
+        let cnf = Cnf::from_dimacs("p cnf 2 2 1 2 0 -1 -2").unwrap();
+        assert_eq!(cnf.clauses.len(), 2);
+        let cnf = Cnf::from_dimacs("p cnf 2 2\n1 2 0\n-1 -2 0\n%\n0\n").unwrap();
+        assert_eq!(cnf.clauses.len(), 2);
+    }
+
+    #[test]
+    fn malformed_files_result_on_error() {
+        match Cnf::from_dimacs("p cnf 2 1\n1 3 0").unwrap_err() {
+            ParserError::UnexpectedToken(_, span, _) => assert_eq!(span, (12, 13).into()),
+            _ => unreachable!()
+        }
+        match Cnf::from_dimacs("p cnf 2 3\n1 2 0").unwrap_err() {
+            ParserError::UnexpectedEOF(..) => {},
+            _ => unreachable!()
+        }
+        match Cnf::from_dimacs("p dnf 2 1\n1 2 0").unwrap_err() {
+            ParserError::UnexpectedToken(_, span, _) => assert_eq!(span, (2, 5).into()),
+            _ => unreachable!()
+        }
+        assert!(matches!(Cnf::from_dimacs("1 2 0"), Err(ParserError::UnexpectedToken(..))));
+        assert!(matches!(Cnf::from_dimacs("p cnf 1 1\n1 ; 0"), Err(ParserError::LexingError(..))));
+    }
+
+    #[test]
+    fn clashing_names_result_on_error() {
+        let cases = [
+            ("c var 1 = p\nc var 2 = p\np cnf 2 1\n1 2 0", (12, 23)),
+            ("c var 1 = x2\np cnf 2 1\n1 2 0", (0, 12)),
+            ("c var 2 = x1\np cnf 2 1\n1 2 0", (0, 12)),
+        ];
+        for (src, span) in cases {
+            match Cnf::from_dimacs(src).unwrap_err() {
+                ParserError::UnexpectedToken(_, s, _) => assert_eq!(s, span.into(), "{src}"),
+                _ => unreachable!()
+            }
+        }
+
+        // Renaming a variable or naming unused ones is fine
+        assert!(Cnf::from_dimacs("c var 1 = p\nc var 1 = q\nc var 3 = q\np cnf 3 1\n1 2 0").is_ok());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::parsing::ASTNode;
+use crate::transforming::{Cnf, Result};
+
+impl Cnf {
+    /// Writes the clauses in the DIMACS CNF format.
+    ///
+    /// Variables are numbered as in [`Cnf::identifiers`] and a comment block
+    /// maps every variable number back to its identifier:
+    ///
+    /// ```txt
+    /// c var 1 = p
+    /// c var 2 = q
+    /// p cnf 2 2
+    /// 1 -2 0
+    /// 2 0
+    /// ```
+    pub fn to_dimacs(&self) -> String {
+        let identifiers = self.identifiers();
+        let mut lines: Vec<String> = identifiers.iter().enumerate()
+            .map(|(i, name)| format!("c var {} = {name}", i + 1))
+            .collect();
+
+        lines.push(format!("p cnf {} {}", identifiers.len(), self.clauses.len()));
+        for clause in self.numbered_clauses() {
+            let literals: Vec<String> = clause.iter().map(|l| l.to_string()).collect();
+            lines.push(format!("{} 0", literals.join(" ")).trim_start().to_owned());
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+impl ASTNode {
+    /// Writes the [`TseitinEncoding`](crate::transforming::TseitinEncoding)
+    /// of the expression in the DIMACS CNF format. See [`Cnf::to_dimacs`].
+    ///
+    /// The output is equisatisfiable with the expression and linear in its
+    /// size, the auxiliary variables are named like `_t0`. For an equivalent
+    /// output, which may be exponentially bigger, write the conjunctive normal
+    /// form instead: `Cnf::from_ast(&ast)?.to_dimacs()`.
+    pub fn to_dimacs(&self) -> Result<String> {
+        Ok(self.to_tseitin()?.cnf.to_dimacs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::TransformError;
+    use crate::parsing::testing::{parse, parse_recovered};
+    use crate::transforming::Cnf;
+
+    #[test]
+    fn dimacs_output_has_a_mapping_block() {
+        let ast = parse("(p -> q) & (q | r) & ~r");
+        assert_eq!(
+            Cnf::from_ast(&ast).unwrap().to_dimacs(),
+            "c var 1 = p\nc var 2 = q\nc var 3 = r\np cnf 3 3\n-1 2 0\n2 3 0\n-3 0\n"
+        );
+    }
+
+    #[test]
+    fn expressions_are_written_in_linear_size() {
+        // The conjunctive normal form would have 2^19 clauses
+        let chain = (0..20).map(|i| format!("p{i}")).collect::<Vec<_>>().join(" <-> ");
+        let dimacs = parse(&chain).to_dimacs().unwrap();
+        assert!(dimacs.contains("p cnf 39 77\n"));
+    }
+
+    #[test]
+    fn dimacs_round_trips() {
+        for expression in ["(p <-> q) | ~(r & s)", "p & ~p", "p | ~p", "~a", "(a ^ b) & (a <-> b)"] {
+            let ast = parse(expression);
+            let read = Cnf::from_dimacs(&ast.to_dimacs().unwrap()).unwrap();
+            assert_eq!(read, Cnf::from_dimacs(&read.to_dimacs()).unwrap());
+
+            // Equisatisfiable, checked against the truth table of the expression
+            let satisfiable = ast.truth_table().unwrap().rows().any(|row| row.result);
+            match read.solve() {
+                Some(model) => assert!(ast.evaluate(&model).unwrap(), "{expression}"),
+                None => assert!(!satisfiable, "{expression}")
+            }
+        }
+    }
+
+    #[test]
+    fn error_nodes_result_on_error() {
+        let ast = parse_recovered("p & $");
+        assert!(matches!(ast.to_dimacs(), Err(TransformError::ErrorNode(Some(_)))));
+    }
+}
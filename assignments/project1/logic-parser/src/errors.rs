@@ -1,12 +1,14 @@
 use thiserror::Error;
 use crate::lexing::token::Span;
+use crate::parsing::Expected;
 
 #[derive(Error, Debug)]
 pub enum ParserError {
+    /// The tokens that were expected instead, empty when unknown
     #[error("Unexpected token: {0}")]
-    UnexpectedToken(String, Span),
+    UnexpectedToken(String, Span, Vec<Expected>),
     #[error("Unexpected EOF: {0}")]
-    UnexpectedEOF(String, Span),
+    UnexpectedEOF(String, Span, Vec<Expected>),
     #[error("Lexing error: {0}")]
     LexingError(LexerError)
     // #[error("Ambiguous Expression: {0}")]
@@ -17,8 +19,97 @@ pub enum ParserError {
 pub enum LexerError {
     #[error("Syntax error: {0}")]
     SyntaxError(String, Span),
+    /// The start of a symbol that isn't complete, with the symbols it could be
+    #[error("Syntax error: expected one of the following: {}", .0.iter().map(|s| format!("'{s}'")).collect::<Vec<String>>().join(", "))]
+    IncompleteSymbol(Vec<String>, Span),
     #[error("Unknown Token: {0}")]
-    UnknownToken(char, Span)
+    UnknownToken(char, Span),
+    #[error("Invalid alphabet: '{0}' can start an identifier but is not part of the alphabet")]
+    InvalidAlphabet(char, Span),
+    #[error("Unterminated string: missing closing {0}")]
+    UnterminatedString(char, Span),
+    #[error("Unterminated comment: missing closing {0}")]
+    UnterminatedComment(String, Span)
+}
+
+#[derive(Error, Debug)]
+pub enum LexerConfigError {
+    #[error("Symbols and keywords can't be empty")]
+    EmptySpelling,
+    #[error("Symbol '{0}' would be read as a word, use a keyword instead")]
+    SymbolLooksLikeWord(String),
+    #[error("Symbol '{0}' contains whitespace")]
+    InvalidSymbol(String),
+    /// The symbol, and the operator and operand it can also be read as
+    #[error("Symbol '{0}' can also be read as '{1}' followed by '{2}'")]
+    AmbiguousSymbol(String, String, String),
+    #[error("Keyword '{0}' can only contain letters, digits and '_'")]
+    InvalidKeyword(String),
+    #[error("Keyword '{0}' is a reserved literal")]
+    ReservedKeyword(String),
+    #[error("'{0}' can't be spelled as an identifier")]
+    IdentifierSpelling(String),
+    #[error("'{0}' can't be used as a quote")]
+    InvalidQuote(char),
+    #[error("Comment delimiter '{0}' is empty or clashes with a symbol or an identifier")]
+    InvalidComment(String)
+}
+
+#[derive(Error, Debug)]
+pub enum EvaluationError {
+    /// The span is the one of the identifier node, if it was parsed
+    #[error("Unassigned identifier: {0}")]
+    UnassignedIdentifier(String, Option<Span>),
+    #[error("Can't evaluate an expression with errors")]
+    ErrorNode(Option<Span>)
+}
+
+#[derive(Error, Debug)]
+pub enum TruthTableError {
+    #[error("Truth tables support at most 127 identifiers, found {0}")]
+    TooManyIdentifiers(usize),
+    #[error("Can't build the truth table of an expression with errors")]
+    ErrorNode(Option<Span>)
+}
+
+#[derive(Error, Debug)]
+pub enum TransformError {
+    #[error("Normal form exceeds the limit of {0} clauses")]
+    ClauseLimitExceeded(usize),
+    #[error("Can't transform an expression with errors")]
+    ErrorNode(Option<Span>)
+}
+
+#[derive(Error, Debug)]
+pub enum MinimizationError {
+    #[error("Minimization supports at most {max} identifiers, found {0}", max = crate::minimizing::MINIMIZATION_MAX_IDENTIFIERS)]
+    TooManyIdentifiers(usize),
+    /// The minterm and the number of identifiers
+    #[error("Minterm {0} is out of range for {1} identifiers")]
+    MintermOutOfRange(u64, usize),
+    /// The expression has error nodes
+    #[error(transparent)]
+    Evaluation(#[from] EvaluationError)
+}
+
+#[derive(Error, Debug)]
+pub enum KarnaughError {
+    #[error("Karnaugh maps need 2 to 6 identifiers, found {0}")]
+    UnsupportedIdentifierCount(usize),
+    /// The expression has an identifier missing from the order, or errors
+    #[error(transparent)]
+    Evaluation(#[from] EvaluationError)
+}
+
+impl ParserError {
+    /// The tokens that would have been accepted where the error happened.
+    pub fn expected(&self) -> &[Expected] {
+        match self {
+            ParserError::UnexpectedToken(_, _, expected) |
+            ParserError::UnexpectedEOF(_, _, expected) => expected,
+            ParserError::LexingError(_) => &[]
+        }
+    }
 }
 
 impl From<LexerError> for ParserError {
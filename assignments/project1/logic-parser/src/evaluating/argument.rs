@@ -0,0 +1,80 @@
+use crate::parsing::{ASTNode, Document};
+use super::assignment::Model;
+use super::equivalence::find_entailment_counterexample;
+use super::evaluate::Result;
+
+impl Document {
+    /// Whether the argument is valid: the premises [`entails`](super::entails) the
+    /// conclusion. `None` if the document has no conclusion, an error if it
+    /// has error nodes.
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::Parser;
+    ///
+    /// let parse = |e| Parser::new(&Lexer::new().tokenize(e).unwrap()).parse_document().unwrap();
+    ///
+    /// assert_eq!(parse("p -> q; q -> r; p |- r").is_valid().unwrap(), Some(true));
+    /// assert_eq!(parse("p -> q; q |- p").is_valid().unwrap(), Some(false));
+    /// assert_eq!(parse("p -> q; q -> r").is_valid().unwrap(), None);
+    /// ```
+    pub fn is_valid(&self) -> Result<Option<bool>> {
+        if self.conclusion.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(self.find_counterexample()?.is_none()))
+    }
+
+    /// Returns an assignment that makes every premise true and the conclusion
+    /// false, or `None` if the argument is valid or has no conclusion.
+    pub fn find_counterexample(&self) -> Result<Option<Model>> {
+        let conclusion = match &self.conclusion {
+            Some(conclusion) => conclusion,
+            None => return Ok(None)
+        };
+        let premises: Vec<ASTNode> = self.premises.iter().map(|s| s.formula.clone()).collect();
+        find_entailment_counterexample(&premises, &conclusion.formula)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::EvaluationError;
+    use crate::parsing::testing::{parse_document, parse_recovered};
+
+    #[test]
+    fn classic_arguments_are_checked() {
+        let cases = [
+            ("p -> q; p |- q", true),
+            ("p -> q; ~q |- ~p", true),
+            ("p | q; ~p |- q", true),
+            ("|- p | ~p", true),
+            ("p; ~p |- q", true),
+            ("p -> q; q |- p", false),
+            ("p -> q; ~p |- ~q", false),
+        ];
+        for (document, valid) in cases {
+            assert_eq!(parse_document(document).is_valid().unwrap(), Some(valid), "{document}");
+        }
+    }
+
+    #[test]
+    fn counterexamples_refute_the_argument() {
+        let document = parse_document("P1: p -> q; P2: q |- C: p");
+        let model = document.find_counterexample().unwrap().unwrap();
+
+        for premise in &document.premises {
+            assert!(premise.formula.evaluate(&model).unwrap());
+        }
+        assert!(!document.conclusion.unwrap().formula.evaluate(&model).unwrap());
+    }
+
+    #[test]
+    fn error_nodes_result_on_error() {
+        let mut document = parse_document("p -> q; p |- q");
+        document.premises[1].formula = parse_recovered("p & $");
+
+        assert!(matches!(document.is_valid(), Err(EvaluationError::ErrorNode(Some(_)))));
+        assert!(matches!(document.find_counterexample(), Err(EvaluationError::ErrorNode(Some(_)))));
+    }
+}
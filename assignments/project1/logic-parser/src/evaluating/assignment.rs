@@ -0,0 +1,52 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A complete assignment of truth values, as returned when looking for
+/// witnesses or counterexamples.
+pub type Model = BTreeMap<String, bool>;
+
+/// A source of truth values for the identifiers of an expression.
+///
+/// Implemented for maps from identifiers to booleans and for slices of
+/// `(identifier, value)` pairs. For computed values use
+/// [`ASTNode::evaluate_with`](crate::parsing::ASTNode::evaluate_with) instead.
+pub trait Assignment {
+    /// Returns the value of `identifier`, or `None` if it is not assigned.
+    fn value_of(&self, identifier: &str) -> Option<bool>;
+}
+
+impl<K, S> Assignment for HashMap<K, bool, S>
+where K: Borrow<str> + Hash + Eq, S: std::hash::BuildHasher {
+    fn value_of(&self, identifier: &str) -> Option<bool> {
+        self.get(identifier).copied()
+    }
+}
+
+impl<K> Assignment for BTreeMap<K, bool>
+where K: Borrow<str> + Ord {
+    fn value_of(&self, identifier: &str) -> Option<bool> {
+        self.get(identifier).copied()
+    }
+}
+
+impl<K> Assignment for [(K, bool)]
+where K: AsRef<str> {
+    fn value_of(&self, identifier: &str) -> Option<bool> {
+        self.iter().find(|(k, _)| k.as_ref() == identifier).map(|(_, v)| *v)
+    }
+}
+
+impl<K, const N: usize> Assignment for [(K, bool); N]
+where K: AsRef<str> {
+    fn value_of(&self, identifier: &str) -> Option<bool> {
+        self.as_slice().value_of(identifier)
+    }
+}
+
+impl<K> Assignment for Vec<(K, bool)>
+where K: AsRef<str> {
+    fn value_of(&self, identifier: &str) -> Option<bool> {
+        self.as_slice().value_of(identifier)
+    }
+}
@@ -0,0 +1,149 @@
+use crate::errors::EvaluationError;
+use crate::parsing::ASTNode;
+use super::assignment::Model;
+use super::evaluate::Result;
+
+/// Semantic classification of an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Classification {
+    /// True under every assignment
+    Tautology,
+    /// False under every assignment
+    Contradiction,
+    /// True under some assignments and false under others
+    Contingency
+}
+
+impl ASTNode {
+    /// Decides whether the expression is a tautology, a contradiction or
+    /// contingent. Fails if the expression has error nodes.
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::Parser;
+    /// use logic_parser::evaluating::Classification;
+    ///
+    /// let tokens = Lexer::new().tokenize("p | ~p").unwrap();
+    /// let ast = Parser::new(&tokens).parse().unwrap();
+    ///
+    /// assert_eq!(ast.classify().unwrap(), Classification::Tautology);
+    /// ```
+    pub fn classify(&self) -> Result<Classification> {
+        let classification = match (self.find_model()?, self.find_counterexample()?) {
+            (Some(_), Some(_)) => Classification::Contingency,
+            (Some(_), None) => Classification::Tautology,
+            (None, _) => Classification::Contradiction,
+        };
+        Ok(classification)
+    }
+
+    pub fn is_tautology(&self) -> Result<bool> {
+        Ok(self.find_counterexample()?.is_none())
+    }
+
+    pub fn is_contradiction(&self) -> Result<bool> {
+        Ok(self.find_model()?.is_none())
+    }
+
+    pub fn is_satisfiable(&self) -> Result<bool> {
+        Ok(self.find_model()?.is_some())
+    }
+
+    /// Returns an assignment of all the identifiers that makes the expression
+    /// true, or `None` if it is unsatisfiable.
+    ///
+    /// The search is done by the [`Solver`](crate::solving::Solver) on the
+    /// [`TseitinEncoding`](crate::transforming::TseitinEncoding) of the
+    /// expression.
+    pub fn find_model(&self) -> Result<Option<Model>> {
+        self.find_assignment(true)
+    }
+
+    /// Returns an assignment of all the identifiers that makes the expression
+    /// false, or `None` if it is a tautology.
+    pub fn find_counterexample(&self) -> Result<Option<Model>> {
+        self.find_assignment(false)
+    }
+
+    fn find_assignment(&self, expected: bool) -> Result<Option<Model>> {
+        if let Some(error) = self.errors().first() {
+            return Err(EvaluationError::ErrorNode(error.span()));
+        }
+
+        let target = if expected {
+            self.clone()
+        }
+        else {
+            ASTNode::Not { operand: Box::new(self.clone()), span: None }
+        };
+        let encoding = target.to_tseitin().expect("error nodes were rejected");
+        let model = encoding.cnf.solve();
+
+        Ok(model.map(|model| encoding.original_model(&model)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::testing::{parse, parse_recovered};
+
+    #[test]
+    fn expressions_are_classified_properly() {
+        assert_eq!(parse("(p -> q) <-> (~q -> ~p)").classify().unwrap(), Classification::Tautology);
+        assert_eq!(parse("p & ~p").classify().unwrap(), Classification::Contradiction);
+        assert_eq!(parse("p -> q").classify().unwrap(), Classification::Contingency);
+        assert_eq!(parse("true").classify().unwrap(), Classification::Tautology);
+        assert_eq!(parse("false").classify().unwrap(), Classification::Contradiction);
+    }
+
+    #[test]
+    fn witnesses_satisfy_the_expression() {
+        let ast = parse("a & ~b & (c | b)");
+        let model = ast.find_model().unwrap().unwrap();
+
+        assert!(ast.evaluate(&model).unwrap());
+        assert_eq!(model, Model::from([("a".into(), true), ("b".into(), false), ("c".into(), true)]));
+        assert!(parse("a & ~a").find_model().unwrap().is_none());
+    }
+
+    #[test]
+    fn counterexamples_falsify_the_expression() {
+        let ast = parse("(p -> q) -> p");
+        let counterexample = ast.find_counterexample().unwrap().unwrap();
+
+        assert!(!ast.evaluate(&counterexample).unwrap());
+        assert!(!ast.is_tautology().unwrap());
+        assert!(ast.is_satisfiable().unwrap());
+        assert!(parse("((p -> q) -> p) -> p").find_counterexample().unwrap().is_none());
+    }
+
+    #[test]
+    fn many_identifiers_are_handled() {
+        // p0 -> p1, p1 -> p2, ..., p0 therefore p39
+        let chain = (0..39).map(|i| format!("(p{i} -> p{})", i + 1)).collect::<Vec<_>>().join(" & ");
+        let ast = parse(&format!("{chain} & p0 -> p39"));
+        assert!(ast.is_tautology().unwrap());
+
+        let ast = parse(&format!("{chain} & p0 & ~p39"));
+        assert!(ast.is_contradiction().unwrap());
+
+        let ast = parse(&(0..40).map(|i| format!("p{i}")).collect::<Vec<_>>().join(" <-> "));
+        assert_eq!(ast.classify().unwrap(), Classification::Contingency);
+    }
+
+    #[test]
+    fn error_nodes_result_on_error() {
+        let ast = parse_recovered("p & $");
+        let span = Some((4, 5).into());
+
+        assert!(matches!(ast.classify(), Err(EvaluationError::ErrorNode(s)) if s == span));
+        assert!(matches!(ast.is_tautology(), Err(EvaluationError::ErrorNode(s)) if s == span));
+        assert!(matches!(ast.is_contradiction(), Err(EvaluationError::ErrorNode(s)) if s == span));
+        assert!(matches!(ast.is_satisfiable(), Err(EvaluationError::ErrorNode(s)) if s == span));
+        assert!(matches!(ast.find_model(), Err(EvaluationError::ErrorNode(s)) if s == span));
+        assert!(matches!(ast.find_counterexample(), Err(EvaluationError::ErrorNode(s)) if s == span));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::parsing::ASTNode;
+use super::assignment::Model;
+use super::evaluate::Result;
+
+impl ASTNode {
+    /// Whether both expressions have the same truth value under every
+    /// assignment of the union of their identifiers. Fails if either has error
+    /// nodes.
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::Parser;
+    ///
+    /// let parse = |e| Parser::new(&Lexer::new().tokenize(e).unwrap()).parse().unwrap();
+    ///
+    /// assert!(parse("p -> q").is_equivalent_to(&parse("~p | q")).unwrap());
+    /// assert!(!parse("p -> q").is_equivalent_to(&parse("q -> p")).unwrap());
+    /// ```
+    pub fn is_equivalent_to(&self, other: &ASTNode) -> Result<bool> {
+        Ok(self.find_difference(other)?.is_none())
+    }
+
+    /// Returns an assignment under which both expressions have different
+    /// truth values, or `None` if they are equivalent.
+    pub fn find_difference(&self, other: &ASTNode) -> Result<Option<Model>> {
+        ASTNode::IfAndOnlyIf {
+            left: Box::new(self.clone()),
+            right: Box::new(other.clone()),
+            span: None
+        }.find_counterexample()
+    }
+}
+
+/// Whether every assignment that makes all the premises true also makes the
+/// conclusion true (`premises ⊨ conclusion`).
+///
+/// With no premises this is the same as asking if the conclusion is a
+/// tautology.
+pub fn entails(premises: &[ASTNode], conclusion: &ASTNode) -> Result<bool> {
+    Ok(find_entailment_counterexample(premises, conclusion)?.is_none())
+}
+
+/// Returns an assignment that makes all the premises true and the conclusion
+/// false, or `None` if the premises entail the conclusion.
+pub fn find_entailment_counterexample(premises: &[ASTNode], conclusion: &ASTNode) -> Result<Option<Model>> {
+    let conjunction = premises.iter().cloned().reduce(|acc, p| {
+        ASTNode::And { left: Box::new(acc), right: Box::new(p), span: None }
+    });
+
+    match conjunction {
+        Some(premises) => ASTNode::Implies {
+            left: Box::new(premises),
+            right: Box::new(conclusion.clone()),
+            span: None
+        }.find_counterexample(),
+        None => conclusion.find_counterexample()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::EvaluationError;
+    use crate::parsing::testing::{parse, parse_recovered};
+
+    #[test]
+    fn known_equivalences_hold() {
+        let cases = [
+            ("p -> q", "~p | q"),
+            ("~(p & q)", "~p | ~q"),
+            ("p <-> q", "(p -> q) & (q -> p)"),
+            ("p & (q | r)", "(p & q) | (p & r)"),
+        ];
+        for (a, b) in cases {
+            assert!(parse(a).is_equivalent_to(&parse(b)).unwrap(), "{a} ≡ {b}");
+        }
+    }
+
+    #[test]
+    fn differences_are_reported_over_all_identifiers() {
+        let a = parse("p");
+        let b = parse("p & q");
+        let difference = a.find_difference(&b).unwrap().unwrap();
+
+        assert_eq!(difference, Model::from([("p".into(), true), ("q".into(), false)]));
+        assert_ne!(a.evaluate(&difference).unwrap(), b.evaluate(&difference).unwrap());
+    }
+
+    #[test]
+    fn entailment_is_checked() {
+        let premises = [parse("p -> q"), parse("q -> r"), parse("p")];
+        assert!(entails(&premises, &parse("r")).unwrap());
+
+        let premises = [parse("p -> q"), parse("q")];
+        let counterexample = find_entailment_counterexample(&premises, &parse("p")).unwrap().unwrap();
+        assert_eq!(counterexample, Model::from([("p".into(), false), ("q".into(), true)]));
+
+        assert!(entails(&[], &parse("p | ~p")).unwrap());
+        assert!(!entails(&[], &parse("p")).unwrap());
+    }
+
+    #[test]
+    fn error_nodes_result_on_error() {
+        let ast = parse("p");
+        let recovered = [parse_recovered("p | $")];
+
+        assert!(matches!(ast.is_equivalent_to(&recovered[0]), Err(EvaluationError::ErrorNode(Some(_)))));
+        assert!(matches!(recovered[0].find_difference(&ast), Err(EvaluationError::ErrorNode(Some(_)))));
+        assert!(matches!(entails(&recovered, &ast), Err(EvaluationError::ErrorNode(Some(_)))));
+        assert!(matches!(find_entailment_counterexample(&[ast], &recovered[0]), Err(EvaluationError::ErrorNode(Some(_)))));
+    }
+}
@@ -0,0 +1,146 @@
+use crate::errors::EvaluationError;
+use crate::parsing::ASTNode;
+use super::assignment::Assignment;
+
+pub type Result<T> = std::result::Result<T, EvaluationError>;
+
+impl ASTNode {
+    /// Computes the truth value of the expression under the given assignment.
+    ///
+    /// Every identifier of the expression must be assigned, even the ones
+    /// that wouldn't change the result (like `q` in `false && q`).
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::Parser;
+    ///
+    /// let tokens = Lexer::new().tokenize("p => q").unwrap();
+    /// let ast = Parser::new(&tokens).parse().unwrap();
+    ///
+    /// let assignment = HashMap::from([("p", true), ("q", false)]);
+    /// assert_eq!(ast.evaluate(&assignment).unwrap(), false);
+    /// ```
+    pub fn evaluate<A>(&self, assignment: &A) -> Result<bool>
+    where A: Assignment + ?Sized {
+        self.evaluate_with(|name| assignment.value_of(name))
+    }
+
+    /// Like [`ASTNode::evaluate`] but the identifiers are resolved by a closure.
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::Parser;
+    ///
+    /// let tokens = Lexer::new().tokenize("tag_a || tag_b").unwrap();
+    /// let ast = Parser::new(&tokens).parse().unwrap();
+    ///
+    /// let tags = ["tag_b", "tag_c"];
+    /// assert!(ast.evaluate_with(|name| Some(tags.contains(&name))).unwrap());
+    /// ```
+    pub fn evaluate_with<F>(&self, resolver: F) -> Result<bool>
+    where F: Fn(&str) -> Option<bool> {
+        self.evaluate_node(&resolver)
+    }
+
+    fn evaluate_node<F>(&self, resolver: &F) -> Result<bool>
+    where F: Fn(&str) -> Option<bool> {
+        match self {
+            ASTNode::Identifier { name, span } => {
+                resolver(name).ok_or_else(|| EvaluationError::UnassignedIdentifier(name.to_owned(), *span))
+            },
+            ASTNode::Literal { value, .. } => Ok(*value),
+            ASTNode::Error { span } => Err(EvaluationError::ErrorNode(*span)),
+            ASTNode::Not { operand, .. } => {
+                Ok(!operand.evaluate_node(resolver)?)
+            },
+            ASTNode::And { left, right, .. } => {
+                let (l, r) = (left.evaluate_node(resolver)?, right.evaluate_node(resolver)?);
+                Ok(l && r)
+            },
+            ASTNode::Or { left, right, .. } => {
+                let (l, r) = (left.evaluate_node(resolver)?, right.evaluate_node(resolver)?);
+                Ok(l || r)
+            },
+            ASTNode::Implies { left, right, .. } => {
+                let (l, r) = (left.evaluate_node(resolver)?, right.evaluate_node(resolver)?);
+                Ok(!l || r)
+            },
+            ASTNode::IfAndOnlyIf { left, right, .. } => {
+                let (l, r) = (left.evaluate_node(resolver)?, right.evaluate_node(resolver)?);
+                Ok(l == r)
+            },
+            ASTNode::Xor { left, right, .. } => {
+                let (l, r) = (left.evaluate_node(resolver)?, right.evaluate_node(resolver)?);
+                Ok(l != r)
+            },
+            ASTNode::Nand { left, right, .. } => {
+                let (l, r) = (left.evaluate_node(resolver)?, right.evaluate_node(resolver)?);
+                Ok(!(l && r))
+            },
+            ASTNode::Nor { left, right, .. } => {
+                let (l, r) = (left.evaluate_node(resolver)?, right.evaluate_node(resolver)?);
+                Ok(!(l || r))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, BTreeMap};
+    use crate::parsing::testing::parse;
+
+    #[test]
+    fn operators_follow_their_truth_tables() {
+        let cases = [
+            ("p & q", [false, false, false, true]),
+            ("p | q", [false, true, true, true]),
+            ("p -> q", [true, true, false, true]),
+            ("p <-> q", [true, false, false, true]),
+            ("p ^ q", [false, true, true, false]),
+            ("p nand q", [true, true, true, false]),
+            ("p nor q", [true, false, false, false]),
+            ("~p", [true, true, false, false]),
+        ];
+
+        for (expression, expected) in cases {
+            let ast = parse(expression);
+            for (i, value) in expected.iter().enumerate() {
+                let assignment = [("p", i & 0b10 != 0), ("q", i & 0b01 != 0)];
+                assert_eq!(ast.evaluate(&assignment).unwrap(), *value, "{expression} at row {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn literals_dont_need_an_assignment() {
+        let ast = parse("true & ~false");
+        assert!(ast.evaluate(&HashMap::<String, bool>::new()).unwrap());
+    }
+
+    #[test]
+    fn any_kind_of_map_can_be_used() {
+        let ast = parse("a & (b | c)");
+        let hashmap: HashMap<String, bool> = HashMap::from([
+            ("a".into(), true), ("b".into(), false), ("c".into(), true)
+        ]);
+        let btreemap = BTreeMap::from([("a", true), ("b", false), ("c", false)]);
+
+        assert!(ast.evaluate(&hashmap).unwrap());
+        assert!(!ast.evaluate(&btreemap).unwrap());
+    }
+
+    #[test]
+    fn unassigned_identifiers_result_on_error() {
+        let ast = parse("false & unknown");
+        match ast.evaluate(&[("known", true)]).unwrap_err() {
+            EvaluationError::UnassignedIdentifier(name, span) => {
+                assert_eq!(name, "unknown");
+                assert_eq!(span, Some((8, 15).into()));
+            },
+            _ => unreachable!()
+        }
+    }
+}
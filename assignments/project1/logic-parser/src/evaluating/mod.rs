@@ -0,0 +1,12 @@
+mod assignment;
+mod evaluate;
+mod truth_table;
+mod classification;
+mod equivalence;
+mod argument;
+
+pub use assignment::*;
+pub use evaluate::*;
+pub use truth_table::*;
+pub use classification::*;
+pub use equivalence::*;
@@ -0,0 +1,284 @@
+use std::collections::HashSet;
+use crate::errors::TruthTableError;
+use crate::parsing::ASTNode;
+
+/// Truth table of an expression.
+///
+/// The table has one input column per identifier, in order of first
+/// appearance, and one output column per distinct sub-expression that is an
+/// operator, from the innermost to the outermost (the last column is always
+/// the whole expression).
+///
+/// Rows are enumerated like binary numbers, the first identifier being the
+/// most significant bit, so the first row assigns `false` to everything. They
+/// are computed lazily by [`TruthTable::rows`], so a big table doesn't need to
+/// fit in memory.
+///
+/// ```
+/// use logic_parser::lexing::Lexer;
+/// use logic_parser::parsing::Parser;
+///
+/// let tokens = Lexer::new().tokenize("p & ~q").unwrap();
+/// let ast = Parser::new(&tokens).parse().unwrap();
+/// let table = ast.truth_table().unwrap();
+///
+/// assert_eq!(table.headers(), vec!["p", "q", "~q", "p & ~q"]);
+/// assert_eq!(
+///     table.rows().map(|row| row.result).collect::<Vec<bool>>(),
+///     vec![false, false, true, false]
+/// );
+/// ```
+#[derive(Debug)]
+pub struct TruthTable<'a> {
+    ast: &'a ASTNode,
+    identifiers: Vec<&'a str>,
+    columns: Vec<&'a ASTNode>
+}
+
+/// A single row of a [`TruthTable`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TruthTableRow {
+    /// Values assigned to the identifiers, in the same order as [`TruthTable::identifiers`]
+    pub inputs: Vec<bool>,
+    /// Values of the sub-expressions, in the same order as [`TruthTable::columns`]
+    pub outputs: Vec<bool>,
+    /// Value of the whole expression
+    pub result: bool
+}
+
+impl ASTNode {
+    /// Builds the [`TruthTable`] of the expression.
+    ///
+    /// Fails if the expression has more than 127 identifiers, since the rows
+    /// couldn't be indexed, or if it has error nodes.
+    pub fn truth_table(&self) -> Result<TruthTable<'_>, TruthTableError> {
+        TruthTable::new(self)
+    }
+}
+
+impl<'a> TruthTable<'a> {
+    pub fn new(ast: &'a ASTNode) -> Result<Self, TruthTableError> {
+        let identifiers = ast.get_ordered_identifiers();
+        if identifiers.len() >= 128 {
+            return Err(TruthTableError::TooManyIdentifiers(identifiers.len()));
+        }
+        if let Some(error) = ast.errors().first() {
+            return Err(TruthTableError::ErrorNode(error.span()));
+        }
+
+        let mut columns: Vec<&ASTNode> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        collect_operators(ast, &mut |node| {
+            if seen.insert(node.to_string()) {
+                columns.push(node);
+            }
+        });
+
+        Ok(TruthTable { ast, identifiers, columns })
+    }
+
+    pub fn identifiers(&self) -> &[&'a str] {
+        &self.identifiers
+    }
+
+    /// The sub-expressions that are evaluated on each row.
+    pub fn columns(&self) -> &[&'a ASTNode] {
+        &self.columns
+    }
+
+    /// Names of all the columns: the identifiers followed by the formatted
+    /// sub-expressions.
+    pub fn headers(&self) -> Vec<String> {
+        self.identifiers.iter().map(|i| i.to_string())
+            .chain(self.columns.iter().map(|c| c.to_string()))
+            .collect()
+    }
+
+    /// Number of rows of the table, `2^n` for `n` identifiers.
+    pub fn len(&self) -> u128 {
+        1_u128 << self.identifiers.len()
+    }
+
+    /// A table always has at least one row, even without identifiers.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Computes the row at the given index.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn row(&self, index: u128) -> TruthTableRow {
+        assert!(index < self.len(), "row index out of bounds");
+        let n = self.identifiers.len();
+        let inputs: Vec<bool> = (0..n).map(|i| index & (1 << (n - 1 - i)) != 0).collect();
+
+        let resolve = |name: &str| {
+            self.identifiers.iter().position(|i| *i == name).map(|i| inputs[i])
+        };
+        let outputs = self.columns.iter()
+            .map(|c| c.evaluate_with(resolve).expect("every identifier is assigned and there are no error nodes"))
+            .collect();
+        let result = self.ast.evaluate_with(resolve).expect("every identifier is assigned and there are no error nodes");
+
+        TruthTableRow { inputs, outputs, result }
+    }
+
+    /// Lazy iterator over all the rows.
+    pub fn rows(&self) -> Rows<'_, 'a> {
+        Rows { table: self, next: 0, end: self.len() }
+    }
+
+    /// Renders the table as strings of `0` and `1`, headers included, like
+    /// the frontend's `generateTable` does.
+    pub fn as_strings(&self) -> Vec<Vec<String>> {
+        let bit = |b: &bool| (if *b { "1" } else { "0" }).to_string();
+        std::iter::once(self.headers())
+            .chain(self.rows().map(|row| {
+                row.inputs.iter().chain(row.outputs.iter()).map(bit).collect()
+            }))
+            .collect()
+    }
+}
+
+/// Iterator over the rows of a [`TruthTable`], created by [`TruthTable::rows`].
+pub struct Rows<'t, 'a> {
+    table: &'t TruthTable<'a>,
+    next: u128,
+    end: u128
+}
+
+impl Iterator for Rows<'_, '_> {
+    type Item = TruthTableRow;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let row = self.table.row(self.next);
+        self.next += 1;
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        match usize::try_from(remaining) {
+            Ok(n) => (n, Some(n)),
+            Err(_) => (usize::MAX, None)
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.next = self.next.saturating_add(n as u128).min(self.end);
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for Rows<'_, '_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(self.table.row(self.end))
+    }
+}
+
+/// Visits the operator nodes of the tree in post-order.
+fn collect_operators<'a, F>(node: &'a ASTNode, visit: &mut F)
+where F: FnMut(&'a ASTNode) {
+    match node {
+        ASTNode::Identifier { .. } | ASTNode::Literal { .. } | ASTNode::Error { .. } => return,
+        ASTNode::Not { operand, .. } => collect_operators(operand, visit),
+        ASTNode::And { left, right, .. } |
+        ASTNode::Or { left, right, .. } |
+        ASTNode::Implies { left, right, .. } |
+        ASTNode::IfAndOnlyIf { left, right, .. } |
+        ASTNode::Xor { left, right, .. } |
+        ASTNode::Nand { left, right, .. } |
+        ASTNode::Nor { left, right, .. } => {
+            collect_operators(left, visit);
+            collect_operators(right, visit);
+        }
+    }
+    visit(node);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::TruthTableError;
+    use crate::parsing::testing::{parse, parse_recovered};
+
+    #[test]
+    fn columns_go_from_inner_to_outer_expressions() {
+        let ast = parse("(p -> q) & ~(q | r)");
+        assert_eq!(
+            ast.truth_table().unwrap().headers(),
+            vec!["p", "q", "r", "p -> q", "q | r", "~(q | r)", "(p -> q) & ~(q | r)"]
+        );
+    }
+
+    #[test]
+    fn repeated_subexpressions_have_a_single_column() {
+        let ast = parse("(p & q) | ~(p & q)");
+        assert_eq!(
+            ast.truth_table().unwrap().headers(),
+            vec!["p", "q", "p & q", "~(p & q)", "p & q | ~(p & q)"]
+        );
+    }
+
+    #[test]
+    fn rows_are_enumerated_in_binary_order() {
+        let ast = parse("a <-> b");
+        let table = ast.truth_table().unwrap();
+        let rows: Vec<_> = table.rows().map(|r| (r.inputs, r.result)).collect();
+        assert_eq!(rows, vec![
+            (vec![false, false], true),
+            (vec![false, true], false),
+            (vec![true, false], false),
+            (vec![true, true], true),
+        ]);
+        assert_eq!(table.as_strings()[2], vec!["0", "1", "0"]);
+    }
+
+    #[test]
+    fn big_tables_are_not_allocated() {
+        let expression = (0..40).map(|i| format!("p{i}")).collect::<Vec<_>>().join(" | ");
+        let ast = parse(&expression);
+        let table = ast.truth_table().unwrap();
+
+        assert_eq!(table.len(), 1 << 40);
+        let mut rows = table.rows();
+        assert!(!rows.next().unwrap().result);
+        assert!(rows.nth(1_000_000_000).unwrap().result);
+        assert!(rows.next_back().unwrap().result);
+    }
+
+    #[test]
+    fn too_many_identifiers_result_on_error() {
+        let expression = (0..128).map(|i| format!("p{i}")).collect::<Vec<_>>().join(" & ");
+        match parse(&expression).truth_table() {
+            Err(TruthTableError::TooManyIdentifiers(n)) => assert_eq!(n, 128),
+            _ => unreachable!()
+        }
+    }
+
+    #[test]
+    fn error_nodes_result_on_error() {
+        let ast = parse_recovered("p & $");
+        match ast.truth_table() {
+            Err(TruthTableError::ErrorNode(span)) => assert_eq!(span, Some((4, 5).into())),
+            _ => unreachable!()
+        }
+    }
+
+    #[test]
+    fn tables_without_identifiers_have_one_row() {
+        let ast = parse("true -> false");
+        let rows: Vec<_> = ast.truth_table().unwrap().rows().collect();
+        assert_eq!(rows.len(), 1);
+        assert!(!rows[0].result);
+    }
+}
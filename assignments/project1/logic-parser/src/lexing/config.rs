@@ -0,0 +1,425 @@
+use crate::errors::LexerConfigError;
+use super::keywords::Keywords;
+use super::token::TokenKind;
+
+/// Syntax profile of the [`Lexer`](super::Lexer): the symbols and keywords
+/// that are read as operators, parentheses or literals.
+///
+/// Symbols are matched before words, always taking the longest one, so `->`
+/// and `-` can live together. Keywords are only matched as whole words.
+/// Every built-in profile reads `"..."` and `'...'` as quoted identifiers,
+/// skips `#` and `//` line comments and `/* ... */` block comments, and treats
+/// newlines as whitespace. The statements of a
+/// [`Document`](crate::parsing::Document) are separated by `;`, labelled with
+/// `:` and the conclusion follows `|-` or `⊢`.
+///
+/// ```
+/// use logic_parser::lexing::{Lexer, LexerConfig, token::TokenKind};
+///
+/// // Prolog-like syntax, plus '=>' for implications
+/// let config = LexerConfig::prolog().with_symbol("=>", TokenKind::Implies);
+/// let mut lexer = Lexer::new().with_config(config).unwrap();
+///
+/// let tokens = lexer.tokenize("a, b => \\+c").unwrap();
+/// assert_eq!(tokens[1].kind, TokenKind::And);
+/// assert_eq!(tokens[3].kind, TokenKind::Implies);
+/// assert_eq!(tokens[4].kind, TokenKind::Not);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexerConfig {
+    symbols: Vec<(String, TokenKind)>,
+    keywords: Keywords,
+    quotes: Vec<char>,
+    line_comments: Vec<String>,
+    block_comments: Vec<(String, String)>,
+    newlines: NewlineMode
+}
+
+/// How the [`Lexer`](super::Lexer) reads line breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NewlineMode {
+    /// Line breaks are whitespace, an expression can span many lines
+    #[default]
+    Whitespace,
+    /// Line breaks end statements and produce a [`TokenKind::Separator`].
+    /// Consecutive line breaks, including empty or commented lines, produce
+    /// a single separator.
+    Separator
+}
+
+impl LexerConfig {
+    /// A profile without symbols, keywords, quotes nor comments, where only
+    /// identifiers, `true` and `false` are recognized.
+    pub fn empty() -> Self {
+        LexerConfig {
+            symbols: vec![],
+            keywords: Keywords::empty(),
+            quotes: vec![],
+            line_comments: vec![],
+            block_comments: vec![],
+            newlines: NewlineMode::Whitespace
+        }
+    }
+
+    /// The default syntax, which accepts most notations at once:
+    ///
+    /// | Operator | Symbols              | Keyword   |
+    /// |----------|----------------------|-----------|
+    /// | not      | `~` `!` `¬`          | `not`     |
+    /// | and      | `&&` `&` `∧`         | `and`     |
+    /// | or       | `\|\|` `\|` `∨`      | `or`      |
+    /// | implies  | `->` `=>` `→` `⇒`    | `implies` |
+    /// | iff      | `<->` `<=>` `↔` `⇔` `⟷` | `iff`  |
+    /// | xor      | `^` `⊕`              | `xor`     |
+    /// | nand     | `↑`                  | `nand`    |
+    /// | nor      | `↓`                  | `nor`     |
+    ///
+    /// `⊤` and `⊥` are the literals `true` and `false`.
+    pub fn standard() -> Self {
+        Self::base()
+            .with_symbol("~", TokenKind::Not)
+            .with_symbol("!", TokenKind::Not)
+            .with_symbol("¬", TokenKind::Not)
+            .with_symbol("&&", TokenKind::And)
+            .with_symbol("&", TokenKind::And)
+            .with_symbol("∧", TokenKind::And)
+            .with_symbol("||", TokenKind::Or)
+            .with_symbol("|", TokenKind::Or)
+            .with_symbol("∨", TokenKind::Or)
+            .with_symbol("->", TokenKind::Implies)
+            .with_symbol("=>", TokenKind::Implies)
+            .with_symbol("→", TokenKind::Implies)
+            .with_symbol("⇒", TokenKind::Implies)
+            .with_symbol("<->", TokenKind::IfAndOnlyIf)
+            .with_symbol("<=>", TokenKind::IfAndOnlyIf)
+            .with_symbol("↔", TokenKind::IfAndOnlyIf)
+            .with_symbol("⇔", TokenKind::IfAndOnlyIf)
+            .with_symbol("⟷", TokenKind::IfAndOnlyIf)
+            .with_symbol("^", TokenKind::Xor)
+            .with_symbol("⊕", TokenKind::Xor)
+            .with_symbol("↑", TokenKind::Nand)
+            .with_symbol("↓", TokenKind::Nor)
+            .with_symbol("⊤", TokenKind::Literal(true))
+            .with_symbol("⊥", TokenKind::Literal(false))
+            .with_keywords(Keywords::standard())
+    }
+
+    /// C-like operators: `!`, `&&`, `||`, `^` (xor), `->` and `<->`.
+    pub fn c_style() -> Self {
+        Self::base()
+            .with_symbol("!", TokenKind::Not)
+            .with_symbol("&&", TokenKind::And)
+            .with_symbol("||", TokenKind::Or)
+            .with_symbol("^", TokenKind::Xor)
+            .with_symbol("->", TokenKind::Implies)
+            .with_symbol("<->", TokenKind::IfAndOnlyIf)
+            .with_keywords(Keywords::minimal())
+    }
+
+    /// The notation of the web frontend truth tables: `!`, `^` (and), `v`
+    /// (or), `->` and `<->`.
+    ///
+    /// `v` is a keyword, so it can't be used as an identifier but `vx` can.
+    pub fn ascii_math() -> Self {
+        Self::base()
+            .with_symbol("!", TokenKind::Not)
+            .with_symbol("~", TokenKind::Not)
+            .with_symbol("^", TokenKind::And)
+            .with_symbol("->", TokenKind::Implies)
+            .with_symbol("<->", TokenKind::IfAndOnlyIf)
+            .with_keywords(Keywords::minimal().with("v", TokenKind::Or))
+    }
+
+    /// Prolog-like operators: `\+` (not), `,` (and), `;` (or), `->` and `<->`.
+    ///
+    /// As `;` is taken, statements can only be separated by newlines.
+    pub fn prolog() -> Self {
+        Self::base()
+            .with_symbol("\\+", TokenKind::Not)
+            .with_symbol(",", TokenKind::And)
+            .with_symbol(";", TokenKind::Or)
+            .with_symbol("->", TokenKind::Implies)
+            .with_symbol("<->", TokenKind::IfAndOnlyIf)
+            .with_keywords(Keywords::minimal())
+    }
+
+    /// Only the [`Keywords::standard`] words, `(p and q) implies not r`.
+    pub fn verbose() -> Self {
+        Self::base().with_keywords(Keywords::standard())
+    }
+
+    fn base() -> Self {
+        Self::empty()
+            .with_symbol("(", TokenKind::OpenParen)
+            .with_symbol(")", TokenKind::CloseParen)
+            .with_symbol(";", TokenKind::Separator)
+            .with_symbol(":", TokenKind::Colon)
+            .with_symbol("|-", TokenKind::Turnstile)
+            .with_symbol("⊢", TokenKind::Turnstile)
+            .with_quotes(&['"', '\''])
+            .with_line_comment("#")
+            .with_line_comment("//")
+            .with_block_comment("/*", "*/")
+    }
+
+    /// Adds (or replaces) a symbol.
+    pub fn with_symbol<S: Into<String>>(mut self, symbol: S, kind: TokenKind) -> Self {
+        let symbol = symbol.into();
+        match self.symbols.iter_mut().find(|(s, _)| *s == symbol) {
+            Some((_, old)) => *old = kind,
+            None => self.symbols.push((symbol, kind))
+        }
+        self
+    }
+
+    /// Removes a symbol.
+    pub fn without_symbol(mut self, symbol: &str) -> Self {
+        self.symbols.retain(|(s, _)| s != symbol);
+        self
+    }
+
+    /// Replaces the keywords.
+    pub fn with_keywords(mut self, keywords: Keywords) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    /// Replaces the characters that open and close quoted identifiers.
+    pub fn with_quotes(mut self, quotes: &[char]) -> Self {
+        self.quotes = quotes.to_vec();
+        self
+    }
+
+    pub fn quotes(&self) -> &[char] {
+        &self.quotes
+    }
+
+    /// Adds a comment that runs until the end of the line.
+    pub fn with_line_comment<S: Into<String>>(mut self, start: S) -> Self {
+        let start = start.into();
+        if !self.line_comments.contains(&start) {
+            self.line_comments.push(start);
+        }
+        self
+    }
+
+    /// Adds a comment that runs until `end`, possibly over many lines.
+    pub fn with_block_comment<S: Into<String>, E: Into<String>>(mut self, start: S, end: E) -> Self {
+        let (start, end) = (start.into(), end.into());
+        self.block_comments.retain(|(s, _)| *s != start);
+        self.block_comments.push((start, end));
+        self
+    }
+
+    /// Removes every line and block comment.
+    pub fn without_comments(mut self) -> Self {
+        self.line_comments.clear();
+        self.block_comments.clear();
+        self
+    }
+
+    pub fn with_newlines(mut self, mode: NewlineMode) -> Self {
+        self.newlines = mode;
+        self
+    }
+
+    pub fn newlines(&self) -> NewlineMode {
+        self.newlines
+    }
+
+    pub fn keywords(&self) -> &Keywords {
+        &self.keywords
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = (&str, &TokenKind)> {
+        self.symbols.iter().map(|(s, k)| (s.as_str(), k))
+    }
+
+    /// Checks that every symbol and keyword can be told apart from the
+    /// identifiers, the literals and each other.
+    ///
+    /// - Symbols can't be empty, contain whitespace or start with a letter, a
+    ///   digit or `_` (use a keyword instead).
+    /// - Symbols can't be spelled as an operator followed by the start of an
+    ///   operand, like `|-` with `-` as a negation.
+    /// - Keywords must be made of letters, digits and `_`, and can't be
+    ///   `true` or `false`.
+    /// - Neither can produce identifiers.
+    /// - Quotes can't be letters, digits, `_`, `\\`, whitespace or the start of
+    ///   a symbol.
+    /// - Comment delimiters can't be empty, and comment starts can't be a
+    ///   prefix of a symbol (or the other way around).
+    pub fn validate(&self) -> Result<(), LexerConfigError> {
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+
+        for quote in &self.quotes {
+            let starts_symbol = self.symbols.iter().any(|(s, _)| s.starts_with(*quote));
+            if is_word_char(*quote) || quote.is_whitespace() || *quote == '\\' || starts_symbol {
+                return Err(LexerConfigError::InvalidQuote(*quote));
+            }
+        }
+
+        for start in self.comment_starts() {
+            let clashes = self.symbols.iter().any(|(s, _)| s.starts_with(start) || start.starts_with(s.as_str()));
+            if start.is_empty() || clashes {
+                return Err(LexerConfigError::InvalidComment(start.to_owned()));
+            }
+        }
+        if let Some((_, end)) = self.block_comments.iter().find(|(_, end)| end.is_empty()) {
+            return Err(LexerConfigError::InvalidComment(end.clone()));
+        }
+
+        for (symbol, kind) in &self.symbols {
+            match symbol.chars().next() {
+                None => return Err(LexerConfigError::EmptySpelling),
+                Some(c) if is_word_char(c) => {
+                    return Err(LexerConfigError::SymbolLooksLikeWord(symbol.clone()))
+                },
+                Some(_) if symbol.chars().any(char::is_whitespace) => {
+                    return Err(LexerConfigError::InvalidSymbol(symbol.clone()))
+                },
+                _ => {}
+            }
+            if let TokenKind::Identifier(_) = kind {
+                return Err(LexerConfigError::IdentifierSpelling(symbol.clone()));
+            }
+        }
+
+        // With `-` as a negation, `|-` would hide `p | -q`
+        for (symbol, _) in &self.symbols {
+            for (prefix, _) in self.symbols.iter().filter(|(_, k)| takes_operand(k)) {
+                let rest = match symbol.strip_prefix(prefix.as_str()) {
+                    Some(rest) if !rest.is_empty() => rest,
+                    _ => continue
+                };
+                if let Some((operand, _)) = self.symbols.iter().find(|(s, k)| rest.starts_with(s.as_str()) && starts_operand(k)) {
+                    return Err(LexerConfigError::AmbiguousSymbol(symbol.clone(), prefix.clone(), operand.clone()));
+                }
+            }
+        }
+
+        for (word, kind) in self.keywords.iter() {
+            if word.is_empty() {
+                return Err(LexerConfigError::EmptySpelling);
+            }
+            if !word.chars().all(is_word_char) {
+                return Err(LexerConfigError::InvalidKeyword(word.to_owned()));
+            }
+            if word == "true" || word == "false" {
+                return Err(LexerConfigError::ReservedKeyword(word.to_owned()));
+            }
+            if let TokenKind::Identifier(_) = kind {
+                return Err(LexerConfigError::IdentifierSpelling(word.to_owned()));
+            }
+        }
+        Ok(())
+    }
+
+    /// The starts of the line and block comments.
+    pub(crate) fn comment_starts(&self) -> impl Iterator<Item = &str> {
+        self.line_comments.iter().chain(self.block_comments.iter().map(|(s, _)| s)).map(String::as_str)
+    }
+
+    /// Removes the line and block comments whose start matches `pred`.
+    pub(crate) fn without_comments_where<F>(mut self, pred: F) -> Self
+    where F: Fn(&str) -> bool {
+        self.line_comments.retain(|start| !pred(start));
+        self.block_comments.retain(|(start, _)| !pred(start));
+        self
+    }
+
+    /// The longest symbol at the start of `src`.
+    pub(crate) fn match_symbol(&self, src: &str) -> Option<(&str, &TokenKind)> {
+        self.symbols()
+            .filter(|(s, _)| src.starts_with(s))
+            .max_by_key(|(s, _)| s.len())
+    }
+
+    /// The length of the line comment starting `src`, without the line break.
+    pub(crate) fn match_line_comment(&self, src: &str) -> Option<usize> {
+        self.line_comments.iter()
+            .any(|start| src.starts_with(start.as_str()))
+            .then(|| src.find('\n').unwrap_or(src.len()))
+    }
+
+    /// The delimiters of the block comment starting `src`.
+    pub(crate) fn match_block_comment(&self, src: &str) -> Option<(&str, &str)> {
+        self.block_comments.iter()
+            .find(|(start, _)| src.starts_with(start.as_str()))
+            .map(|(start, end)| (start.as_str(), end.as_str()))
+    }
+
+    /// Symbols starting with `c`, longest first, for error messages.
+    pub(crate) fn symbols_starting_with(&self, c: char) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self.symbols()
+            .map(|(s, _)| s)
+            .filter(|s| s.starts_with(c))
+            .collect();
+        symbols.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        symbols
+    }
+}
+
+/// Whether an operand can follow the token.
+fn takes_operand(kind: &TokenKind) -> bool {
+    !matches!(kind, TokenKind::Identifier(_) | TokenKind::Literal(_) | TokenKind::CloseParen | TokenKind::Error)
+}
+
+/// Whether the token can start an operand.
+fn starts_operand(kind: &TokenKind) -> bool {
+    matches!(kind, TokenKind::Literal(_) | TokenKind::Not | TokenKind::OpenParen)
+}
+
+impl Default for LexerConfig {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_profiles_are_valid() {
+        let profiles = [
+            LexerConfig::standard(),
+            LexerConfig::c_style(),
+            LexerConfig::ascii_math(),
+            LexerConfig::prolog(),
+            LexerConfig::verbose(),
+        ];
+        for profile in profiles {
+            assert!(profile.validate().is_ok(), "{profile:?}");
+        }
+    }
+
+    #[test]
+    fn ambiguous_configurations_are_rejected() {
+        let invalid = [
+            LexerConfig::standard().with_symbol("", TokenKind::Not),
+            LexerConfig::standard().with_symbol("v", TokenKind::Or),
+            LexerConfig::standard().with_symbol("- >", TokenKind::Implies),
+            LexerConfig::standard().with_symbol("@", TokenKind::Identifier("at".into())),
+            LexerConfig::standard().with_keywords(Keywords::empty().with("true", TokenKind::Literal(false))),
+            LexerConfig::standard().with_keywords(Keywords::empty().with("a-b", TokenKind::And)),
+            LexerConfig::standard().with_quotes(&['|']),
+            LexerConfig::standard().with_quotes(&['q']),
+            LexerConfig::standard().with_line_comment("&"),
+            LexerConfig::standard().with_line_comment("-"),
+            LexerConfig::standard().with_block_comment("/*", ""),
+            LexerConfig::standard().with_symbol("-", TokenKind::Not),
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+
+    #[test]
+    fn longest_symbol_wins() {
+        let config = LexerConfig::standard().with_symbol("-", TokenKind::Not);
+        assert_eq!(config.match_symbol("->p"), Some(("->", &TokenKind::Implies)));
+        assert_eq!(config.match_symbol("-p"), Some(("-", &TokenKind::Not)));
+        assert_eq!(config.symbols_starting_with('<'), vec!["<->", "<=>"]);
+    }
+}
@@ -0,0 +1,90 @@
+use super::token::TokenKind;
+
+const STANDARD: [(&str, TokenKind); 8] = [
+    ("not", TokenKind::Not),
+    ("and", TokenKind::And),
+    ("or", TokenKind::Or),
+    ("implies", TokenKind::Implies),
+    ("iff", TokenKind::IfAndOnlyIf),
+    ("xor", TokenKind::Xor),
+    ("nand", TokenKind::Nand),
+    ("nor", TokenKind::Nor),
+];
+
+/// Words that the [`Lexer`](super::Lexer) reads as operators instead of
+/// identifiers.
+///
+/// `true` and `false` are always literals and are not part of the set.
+///
+/// ```
+/// use logic_parser::lexing::{Lexer, Keywords, token::TokenKind};
+///
+/// // 'and' is a regular identifier, but 'y' means '&&'
+/// let keywords = Keywords::standard().without("and").with("y", TokenKind::And);
+/// let tokens = Lexer::new().with_keywords(keywords).unwrap().tokenize("and y or").unwrap();
+///
+/// assert_eq!(tokens[0].kind, TokenKind::Identifier("and".into()));
+/// assert_eq!(tokens[1].kind, TokenKind::And);
+/// assert_eq!(tokens[2].kind, TokenKind::Or);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keywords {
+    words: Vec<(String, TokenKind)>
+}
+
+impl Keywords {
+    /// A set with no keywords, every word is an identifier.
+    pub fn empty() -> Self {
+        Keywords { words: vec![] }
+    }
+
+    /// `not`, `and`, `or`, `implies`, `iff`, `xor`, `nand` and `nor`.
+    pub fn standard() -> Self {
+        Keywords { words: STANDARD.iter().map(|(w, k)| (w.to_string(), k.clone())).collect() }
+    }
+
+    /// Whether `word` is one of the [`Keywords::standard`] keywords.
+    pub(crate) fn is_standard(word: &str) -> bool {
+        STANDARD.iter().any(|(w, _)| *w == word)
+    }
+
+    /// Only the derived operators `xor`, `nand` and `nor`, which have no
+    /// ASCII symbol.
+    pub fn minimal() -> Self {
+        Self::empty()
+            .with("xor", TokenKind::Xor)
+            .with("nand", TokenKind::Nand)
+            .with("nor", TokenKind::Nor)
+    }
+
+    /// Adds (or replaces) a keyword.
+    pub fn with<S: Into<String>>(mut self, word: S, kind: TokenKind) -> Self {
+        let word = word.into();
+        match self.words.iter_mut().find(|(w, _)| *w == word) {
+            Some((_, old)) => *old = kind,
+            None => self.words.push((word, kind))
+        }
+        self
+    }
+
+    /// Removes a keyword, so it can be used as an identifier.
+    pub fn without(mut self, word: &str) -> Self {
+        self.words.retain(|(w, _)| w != word);
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TokenKind)> {
+        self.words.iter().map(|(w, k)| (w.as_str(), k))
+    }
+
+    /// Returns the token of the keyword, if `word` is one.
+    pub fn get(&self, word: &str) -> Option<&TokenKind> {
+        self.words.iter().find(|(w, _)| w == word).map(|(_, k)| k)
+    }
+}
+
+impl Default for Keywords {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
@@ -1,12 +1,20 @@
 use std::ops::Not;
-use crate::errors::LexerError;
+use std::rc::Rc;
+use crate::errors::{LexerError, LexerConfigError};
+use crate::diagnostics::SourceError;
 use super::token::{TokenKind, Token};
+use super::keywords::Keywords;
+use super::config::{LexerConfig, NewlineMode};
 
 pub type Result<T> = std::result::Result<T, LexerError>;
 
+/// Predicate that tells which characters belong to an alphabet.
+pub type Alphabet<'a> = Rc<dyn Fn(char) -> bool + 'a>;
+
 pub struct Lexer<'a> {
-    is_in_alphabet: fn(char) -> bool,
-    is_in_start_chars_alphabet: fn(char) -> bool,
+    is_in_alphabet: Alphabet<'a>,
+    is_in_start_chars_alphabet: Alphabet<'a>,
+    config: LexerConfig,
     src: &'a str,
     pos: usize
 }
@@ -15,7 +23,8 @@ pub const DEFAULT_ALPHABET: fn(char) -> bool = |c| { char::is_alphanumeric(c) ||
 pub const DEFAULT_START_ALPHABET: fn(char) -> bool = |c| { char::is_alphabetic(c) || c == '_' };
 
 impl<'a> Lexer<'a> {
-    /// Creates a new Lexer with the default alphabet.
+    /// Creates a new Lexer with the default alphabet and the
+    /// [`LexerConfig::standard`] syntax.
     ///
     /// This is conceptually equivalent of doing
     ///
@@ -28,12 +37,7 @@ impl<'a> Lexer<'a> {
     /// );
     /// ```
     pub fn new() -> Self {
-        Lexer {
-            is_in_alphabet: DEFAULT_ALPHABET,
-            is_in_start_chars_alphabet: DEFAULT_START_ALPHABET,
-            src: "",
-            pos: 0
-        }
+        Self::with_alphabets(DEFAULT_ALPHABET, DEFAULT_START_ALPHABET)
     }
 
     /// This allows you to define a custom alphabet for the lexer.
@@ -54,20 +58,38 @@ impl<'a> Lexer<'a> {
     /// parser.parse().unwrap();
     /// ```
     ///
-    /// # WARNING
+    /// Alphabets can be any closure, so they can be built at runtime:
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
     ///
-    /// Be aware of the following:
+    /// let extra: Vec<char> = "-.".chars().collect();
+    /// let mut lexer = Lexer::with_alphabets(
+    ///     move |c| c.is_alphanumeric() || extra.contains(&c),
+    ///     |c| c.is_alphabetic(),
+    /// );
+    /// lexer.tokenize("user.name & first-login").unwrap();
+    /// ```
     ///
-    /// - Creating an alphabet such that the `start_chars_alphabet` contains `alphabet` plus
-    ///   some other characters is **undefined behaviour**. It will probably loop forever.
+    /// # Errors
     ///
-    pub fn with_alphabets(alphabet: fn(char) -> bool, start_chars_alphabet: fn(char) -> bool) -> Self {
-        Lexer {
-            is_in_alphabet: alphabet,
-            is_in_start_chars_alphabet: start_chars_alphabet,
+    /// The `start_chars_alphabet` is expected to be a subset of `alphabet`.
+    /// Tokenizing a word that starts with a character outside of `alphabet`
+    /// returns [`LexerError::InvalidAlphabet`].
+    ///
+    /// Comments that start with a character of either alphabet are left out,
+    /// so `/` in the alphabet disables `//` and `/* ... */` comments.
+    pub fn with_alphabets<F, G>(alphabet: F, start_chars_alphabet: G) -> Self
+    where F: Fn(char) -> bool + 'a, G: Fn(char) -> bool + 'a {
+        let mut lexer = Lexer {
+            is_in_alphabet: Rc::new(alphabet),
+            is_in_start_chars_alphabet: Rc::new(start_chars_alphabet),
+            config: LexerConfig::standard(),
             src: "",
             pos: 0
-        }
+        };
+        lexer.config = lexer.config.clone().without_comments_where(|start| lexer.is_in_identifier(start));
+        lexer
     }
 
     /// Creates a lexer that uses the same alphabet for the start characters and the rest.
@@ -82,8 +104,59 @@ impl<'a> Lexer<'a> {
     /// let mut lexer = Lexer::with_alphabets(custom_alphabet, custom_alphabet);
     /// lexer.tokenize("_puppies_").unwrap();
     /// ```
-    pub fn with_alphabet(alphabet: fn(char) -> bool) -> Self {
-        Self::with_alphabets(alphabet, alphabet)
+    pub fn with_alphabet<F>(alphabet: F) -> Self
+    where F: Fn(char) -> bool + 'a {
+        let alphabet: Alphabet<'a> = Rc::new(alphabet);
+        let mut lexer = Lexer {
+            is_in_start_chars_alphabet: Rc::clone(&alphabet),
+            is_in_alphabet: alphabet,
+            config: LexerConfig::standard(),
+            src: "",
+            pos: 0
+        };
+        lexer.config = lexer.config.clone().without_comments_where(|start| lexer.is_in_identifier(start));
+        lexer
+    }
+
+    /// Replaces the words that are read as operators. Returns an error if
+    /// the keywords are invalid, see [`LexerConfig::validate`].
+    ///
+    /// ```
+    /// use logic_parser::lexing::{Lexer, Keywords, token::TokenKind};
+    ///
+    /// let mut lexer = Lexer::new().with_keywords(Keywords::empty()).unwrap();
+    /// let tokens = lexer.tokenize("not").unwrap();
+    /// assert_eq!(tokens[0].kind, TokenKind::Identifier("not".into()));
+    /// ```
+    pub fn with_keywords(self, keywords: Keywords) -> std::result::Result<Self, LexerConfigError> {
+        let config = self.config.clone().with_keywords(keywords);
+        self.with_config(config)
+    }
+
+    /// Replaces the syntax profile, see [`LexerConfig`]. Returns an error if
+    /// the profile is ambiguous, or if a comment could start an identifier
+    /// of the alphabets of the lexer.
+    ///
+    /// ```
+    /// use logic_parser::lexing::{Lexer, LexerConfig, token::TokenKind};
+    ///
+    /// let mut lexer = Lexer::new().with_config(LexerConfig::ascii_math()).unwrap();
+    /// let tokens = lexer.tokenize("p ^ q v !r").unwrap();
+    /// assert_eq!(tokens[1].kind, TokenKind::And);
+    /// assert_eq!(tokens[3].kind, TokenKind::Or);
+    /// ```
+    pub fn with_config(mut self, config: LexerConfig) -> std::result::Result<Self, LexerConfigError> {
+        config.validate()?;
+        if let Some(start) = config.comment_starts().find(|start| self.is_in_identifier(start)) {
+            return Err(LexerConfigError::InvalidComment(start.to_owned()));
+        }
+        self.config = config;
+        Ok(self)
+    }
+
+    /// Whether the first character of `text` can be part of an identifier.
+    fn is_in_identifier(&self, text: &str) -> bool {
+        text.chars().next().is_some_and(|c| (self.is_in_alphabet)(c) || (self.is_in_start_chars_alphabet)(c))
     }
 
     pub fn tokenize(&mut self, src: &'a str) -> Result<Vec<Token>> {
@@ -97,8 +170,49 @@ impl<'a> Lexer<'a> {
         Ok(tokens)
     }
 
+    /// Like [`Lexer::tokenize`] but doesn't stop at the first error. The
+    /// source of every error is replaced by a [`TokenKind::Error`] token and
+    /// the lexer goes on after it.
+    ///
+    /// ```
+    /// use logic_parser::lexing::{Lexer, token::TokenKind};
+    ///
+    /// let (tokens, errors) = Lexer::new().tokenize_recovering("p & $ | \"q");
+    /// let kinds: Vec<TokenKind> = tokens.into_iter().map(|t| t.kind).collect();
+    ///
+    /// assert_eq!(kinds, vec![
+    ///     TokenKind::Identifier("p".into()), TokenKind::And, TokenKind::Error, TokenKind::Or, TokenKind::Error
+    /// ]);
+    /// assert_eq!(errors.len(), 2);
+    /// ```
+    pub fn tokenize_recovering(&mut self, src: &'a str) -> (Vec<Token>, Vec<LexerError>) {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        self.src = src;
+        self.pos = 0;
+
+        loop {
+            let from = self.pos;
+            match self.next_token() {
+                Ok(Some(token)) => tokens.push(token),
+                Ok(None) => break,
+                Err(error) => {
+                    let span = error.span();
+                    // Always move forward, to the end of a character
+                    self.pos = self.pos.max(span.end).max(from + 1).min(src.len());
+                    while !src.is_char_boundary(self.pos) {
+                        self.pos += 1;
+                    }
+                    tokens.push(Token::new(TokenKind::Error, (span.start, self.pos)));
+                    errors.push(error);
+                }
+            }
+        }
+        (tokens, errors)
+    }
+
     fn next_token(&mut self) -> Result<Option<Token>> {
-        self.skip_whitespaces();
+        self.skip_trivia()?;
         let start = self.pos;
 
         let c = match self.peek_char() {
@@ -106,80 +220,164 @@ impl<'a> Lexer<'a> {
             None => return Ok(None)
         };
 
-        let kind = match c {
-            '~' | '!' => { self.consume(); TokenKind::Not },
-            '(' => { self.consume(); TokenKind::OpenParen },
-            ')' => { self.consume(); TokenKind::CloseParen },
-            '&' => {
-                match_any_or_syntax_error!(self, ["&&", "&"], TokenKind::And)
-            },
-            '|' => {
-                match_any_or_syntax_error!(self, ["||", "|"], TokenKind::Or)
-            },
-            '=' | '-' => {
-                match_any_or_syntax_error!(self, ["=>", "->"], TokenKind::Implies)
-            },
-            '<' => {
-                match_any_or_syntax_error!(self, ["<->", "<=>"], TokenKind::IfAndOnlyIf)
-            },
-            c if (self.is_in_start_chars_alphabet)(c) => {
-                self.next_word()
-            },
-            _ => {
-                return Err(
-                    LexerError::UnknownToken(c, (start, start+1).into())
-                )
+        if c == '\n' {
+            // Only reached when newlines are separators, blank and commented
+            // lines are merged into the same separator
+            let mut end = start;
+            while self.peek_char() == Some('\n') {
+                self.pos += 1;
+                end = self.pos;
+                self.skip_trivia()?;
             }
-        };
-
-        Ok(Some(Token::new(kind, (start, self.pos))))
-    }
-
-    fn consume(&mut self) -> Option<char> {
-        let next = self.src[self.pos..].chars().next();
-        if let Some(c) = next {
-            self.pos += c.len_utf8();
+            return Ok(Some(Token::new(TokenKind::Separator, (start, end))));
         }
-        next
-    }
 
-    fn skip(&mut self, n: usize) {
-        for _ in 0..n {
-            self.consume();
+        let kind = if self.config.quotes().contains(&c) {
+            self.next_quoted(c)?
         }
-    }
-
-    fn next_matches(&self, to_match: &str) -> bool {
-        let mut chars = self.src[self.pos..].chars();
-        for c in to_match.chars() {
-            if chars.next() != Some(c) {
-                return false;
+        else if let Some((symbol, kind)) = self.config.match_symbol(&self.src[self.pos..]) {
+            let kind = kind.clone();
+            self.pos += symbol.len();
+            kind
+        }
+        else if (self.is_in_start_chars_alphabet)(c) {
+            if !(self.is_in_alphabet)(c) {
+                return Err(LexerError::InvalidAlphabet(c, (start, start + c.len_utf8()).into()));
             }
+            self.next_word()
         }
-        true
+        else {
+            let expected = self.config.symbols_starting_with(c);
+            if expected.is_empty() {
+                return Err(LexerError::UnknownToken(c, (start, start + c.len_utf8()).into()));
+            }
+            return Err(
+                LexerError::IncompleteSymbol(
+                    expected.into_iter().map(String::from).collect(),
+                    (start, start + c.len_utf8()).into()
+                )
+            );
+        };
+
+        Ok(Some(Token::new(kind, (start, self.pos))))
     }
 
     fn peek_char(&self) -> Option<char> {
         self.src[self.pos..].chars().next()
     }
 
-    /// A word can be a literal ([`TokenKind::Literal`]) or an identifier
-    /// ([`TokenKind::Identifier`])
+    /// A word can be a literal ([`TokenKind::Literal`]), one of the
+    /// [`Keywords`] of the lexer or an identifier ([`TokenKind::Identifier`])
     fn next_word(&mut self) -> TokenKind {
         let start = self.pos;
-        // We add one because we already consumed the first character
-        let token_len = self.take_while(self.is_in_alphabet);
+        let alphabet = Rc::clone(&self.is_in_alphabet);
+        let token_len = self.take_while(|c| alphabet(c));
         let p = &self.src[start..start + token_len];
         if p == "true" || p == "false" {
-            TokenKind::Literal(p == "true")
+            return TokenKind::Literal(p == "true");
         }
-        else {
-            TokenKind::Identifier(p.into())
+        match self.config.keywords().get(p) {
+            Some(kind) => kind.clone(),
+            None => TokenKind::Identifier(p.into())
+        }
+    }
+
+    /// Reads an identifier between `quote`s, like `"tag:hot pink"`.
+    ///
+    /// The escape sequences are `\\`, `\"`, `\'`, `\n`, `\t`, `\r` and
+    /// `\u{...}` with a hexadecimal code point.
+    fn next_quoted(&mut self, quote: char) -> Result<TokenKind> {
+        let start = self.pos;
+        self.pos += quote.len_utf8();
+        let mut name = String::new();
+        // Reported once the closing quote is found, so the rest of the
+        // identifier isn't read as tokens
+        let mut invalid_escape = None;
+
+        loop {
+            let c = match self.peek_char() {
+                Some(c) => c,
+                None => return Err(LexerError::UnterminatedString(quote, (start, self.pos).into()))
+            };
+            let escape_start = self.pos;
+            self.pos += c.len_utf8();
+
+            if c == quote {
+                break;
+            }
+            if c != '\\' {
+                name.push(c);
+                continue;
+            }
+
+            let escaped = match self.peek_char() {
+                Some(e) => e,
+                None => return Err(LexerError::UnterminatedString(quote, (start, self.pos).into()))
+            };
+            self.pos += escaped.len_utf8();
+            let unescaped = match escaped {
+                '\\' | '"' | '\'' => Some(escaped),
+                'n' => Some('\n'),
+                't' => Some('\t'),
+                'r' => Some('\r'),
+                'u' => self.next_code_point(),
+                _ => None
+            };
+            match unescaped {
+                Some(u) => name.push(u),
+                None => {
+                    invalid_escape.get_or_insert_with(|| LexerError::SyntaxError(
+                        format!("invalid escape sequence '{}'", &self.src[escape_start..self.pos]),
+                        (escape_start, self.pos).into()
+                    ));
+                }
+            }
+        }
+
+        if let Some(error) = invalid_escape {
+            return Err(error);
+        }
+        if name.is_empty() {
+            return Err(LexerError::SyntaxError("quoted identifiers can't be empty".into(), (start, self.pos).into()));
         }
+        Ok(TokenKind::Identifier(name))
+    }
+
+    /// Reads the `{...}` of a `\u{...}` escape.
+    fn next_code_point(&mut self) -> Option<char> {
+        let rest = self.src[self.pos..].strip_prefix('{')?;
+        let end = rest.find('}')?;
+        let c = u32::from_str_radix(&rest[..end], 16).ok().and_then(char::from_u32)?;
+        self.pos += end + 2;
+        Some(c)
     }
 
     fn skip_whitespaces(&mut self) -> usize {
-        self.take_while(|c| c == '\t' || c == ' ' || c == '\r')
+        let newlines = self.config.newlines() == NewlineMode::Whitespace;
+        self.take_while(|c| c == '\t' || c == ' ' || c == '\r' || (newlines && c == '\n'))
+    }
+
+    /// Skips whitespaces and comments.
+    fn skip_trivia(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespaces();
+            let rest = &self.src[self.pos..];
+
+            if let Some(len) = self.config.match_line_comment(rest) {
+                self.pos += len;
+            }
+            else if let Some((start, end)) = self.config.match_block_comment(rest) {
+                match rest[start.len()..].find(end) {
+                    Some(i) => self.pos += start.len() + i + end.len(),
+                    None => return Err(
+                        LexerError::UnterminatedComment(end.to_owned(), (self.pos, self.src.len()).into())
+                    )
+                }
+            }
+            else {
+                return Ok(());
+            }
+        }
     }
 
     fn take_while<F>(&mut self, pred: F) -> usize
@@ -229,6 +427,206 @@ mod tests {
         );
     }
 
+    #[test]
+    fn derived_operators_have_symbols_and_keywords() {
+        let mut lexer = Lexer::new();
+        let tokens = lexer.tokenize("a ^ b ⊕ c xor d ↑ e nand f ↓ g nor h norm").unwrap();
+        assert_eq!(
+            tokens.iter().skip(1).step_by(2).take(7).map(|t| &t.kind).collect::<Vec<&TokenKind>>(),
+            vec![
+                &TokenKind::Xor, &TokenKind::Xor, &TokenKind::Xor,
+                &TokenKind::Nand, &TokenKind::Nand,
+                &TokenKind::Nor, &TokenKind::Nor,
+            ]
+        );
+        assert_eq!(tokens.last().unwrap().kind, TokenKind::Identifier("norm".into()));
+        assert_eq!(tokens[3].span, (6, 9).into());
+    }
+
+    #[test]
+    fn unicode_and_keyword_operators_work() {
+        let mut lexer = Lexer::new();
+        let symbols = lexer.tokenize("¬p ∧ q ∨ r → s ⇒ t ↔ u ⇔ ⊤ ∧ ⊥").unwrap();
+        let keywords = lexer.tokenize("not p and q or r implies s implies t iff u iff true and false").unwrap();
+        assert_eq!(
+            symbols.iter().map(|t| &t.kind).collect::<Vec<&TokenKind>>(),
+            keywords.iter().map(|t| &t.kind).collect::<Vec<&TokenKind>>()
+        );
+        assert_eq!(symbols[2].span, (4, 7).into());
+
+        let mut lexer = Lexer::new().with_keywords(Keywords::minimal()).unwrap();
+        let tokens = lexer.tokenize("and or ∧").unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| &t.kind).collect::<Vec<&TokenKind>>(),
+            vec![
+                &TokenKind::Identifier("and".into()),
+                &TokenKind::Identifier("or".into()),
+                &TokenKind::And
+            ]
+        );
+    }
+
+    #[test]
+    fn syntax_profiles_can_be_swapped() {
+        let mut lexer = Lexer::new().with_config(LexerConfig::c_style()).unwrap();
+        assert!(lexer.tokenize("!a && (b || c) ^ d").is_ok());
+        assert!(matches!(lexer.tokenize("a & b"), Err(LexerError::IncompleteSymbol(..))));
+        assert!(matches!(lexer.tokenize("a ∧ b"), Err(LexerError::UnknownToken('∧', _))));
+
+        let mut lexer = Lexer::new().with_config(LexerConfig::verbose()).unwrap();
+        let tokens = lexer.tokenize("not (p and q)").unwrap();
+        assert_eq!(tokens[0].kind, TokenKind::Not);
+
+        assert!(Lexer::new().with_config(LexerConfig::standard().with_symbol("x", TokenKind::Xor)).is_err());
+    }
+
+    #[test]
+    fn incomplete_symbols_result_on_syntax_error() {
+        let mut lexer = Lexer::new();
+        let error = lexer.tokenize("p <- q").unwrap_err();
+        assert_eq!(error.to_string(), "Syntax error: expected one of the following: '<->', '<=>'");
+        match error {
+            LexerError::IncompleteSymbol(symbols, span) => {
+                assert_eq!(symbols, vec!["<->", "<=>"]);
+                assert_eq!(span, (2, 3).into());
+            },
+            _ => unreachable!()
+        };
+        assert!(matches!(lexer.tokenize("p - q"), Err(LexerError::IncompleteSymbol(..))));
+
+        let mut lexer = Lexer::new().with_config(LexerConfig::standard().with_symbol("§§", TokenKind::Not)).unwrap();
+        match lexer.tokenize("p & § q") {
+            Err(LexerError::IncompleteSymbol(symbols, span)) => {
+                assert_eq!(symbols, vec!["§§"]);
+                assert_eq!(span, (4, 6).into());
+            },
+            other => panic!("unexpected result {other:?}")
+        };
+    }
+
+    #[test]
+    fn invalid_keywords_are_rejected() {
+        assert!(Lexer::new().with_keywords(Keywords::standard().with("true", TokenKind::And)).is_err());
+        assert!(Lexer::new().with_keywords(Keywords::standard().with("a-b", TokenKind::And)).is_err());
+    }
+
+    #[test]
+    fn quoted_identifiers_work() {
+        let mut lexer = Lexer::new();
+        let tokens = lexer.tokenize(r#""tag:hot pink" && 'mime:image/*' | "say \"hi\"\n" & '\u{3bb}'"#).unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| &t.kind).collect::<Vec<&TokenKind>>(),
+            vec![
+                &TokenKind::Identifier("tag:hot pink".into()),
+                &TokenKind::And,
+                &TokenKind::Identifier("mime:image/*".into()),
+                &TokenKind::Or,
+                &TokenKind::Identifier("say \"hi\"\n".into()),
+                &TokenKind::And,
+                &TokenKind::Identifier("λ".into()),
+            ]
+        );
+        // Spans include the quotes
+        assert_eq!(tokens[0].span, (0, 14).into());
+        assert_eq!(tokens[2].span, (18, 32).into());
+        assert_eq!(lexer.tokenize("'and'").unwrap()[0].kind, TokenKind::Identifier("and".into()));
+    }
+
+    #[test]
+    fn malformed_quoted_identifiers_result_on_error() {
+        let mut lexer = Lexer::new();
+        match lexer.tokenize("p & \"tag:pink").unwrap_err() {
+            LexerError::UnterminatedString(quote, span) => {
+                assert_eq!(quote, '"');
+                assert_eq!(span, (4, 13).into());
+            },
+            _ => unreachable!()
+        };
+        match lexer.tokenize(r#""a\qb""#).unwrap_err() {
+            LexerError::SyntaxError(_, span) => assert_eq!(span, (2, 4).into()),
+            _ => unreachable!()
+        };
+        assert_eq!(lexer.tokenize("'it\\'s'").unwrap()[0].kind, TokenKind::Identifier("it's".into()));
+        assert!(matches!(lexer.tokenize("'it\\'"), Err(LexerError::UnterminatedString('\'', _))));
+        assert!(matches!(lexer.tokenize("''"), Err(LexerError::SyntaxError(..))));
+        assert!(matches!(lexer.tokenize("'\\u{110000}'"), Err(LexerError::SyntaxError(..))));
+    }
+
+    #[test]
+    fn recovering_lexer_goes_on_after_errors() {
+        let src = r#"'x\q' & ¿p | "open"#;
+        let (tokens, errors) = Lexer::new().tokenize_recovering(src);
+        let kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+
+        assert_eq!(kinds, vec![
+            &TokenKind::Error, &TokenKind::And, &TokenKind::Error,
+            &TokenKind::Identifier("p".into()), &TokenKind::Or, &TokenKind::Error
+        ]);
+        // The rest of the quoted identifier isn't read as tokens
+        assert_eq!(tokens[0].span, (2, 5).into());
+        assert_eq!(tokens[2].span, (8, 10).into());
+        assert_eq!(tokens[5].span, (14, 19).into());
+        assert_eq!(errors.len(), 3);
+
+        let (tokens, errors) = Lexer::new().tokenize_recovering("p & q");
+        assert_eq!((tokens.len(), errors.len()), (3, 0));
+    }
+
+    #[test]
+    fn newlines_and_comments_are_skipped() {
+        let src = "# premises\n(p -> q) // modus ponens\n  & p /* multi\nline */ -> q\n";
+        let tokens = Lexer::new().tokenize(src).unwrap();
+        assert_eq!(tokens.len(), 9);
+        assert_eq!(tokens[0].span, (11, 12).into());
+
+        match Lexer::new().tokenize("p /* & q").unwrap_err() {
+            LexerError::UnterminatedComment(end, span) => {
+                assert_eq!(end, "*/");
+                assert_eq!(span, (2, 8).into());
+            },
+            _ => unreachable!()
+        };
+        // Comment markers inside quotes are part of the identifier
+        let tokens = Lexer::new().tokenize("'#tag' // comment").unwrap();
+        assert_eq!(tokens[0].kind, TokenKind::Identifier("#tag".into()));
+    }
+
+    #[test]
+    fn comments_cant_start_identifiers() {
+        // '/' is part of the alphabet, so '//' and '/*' aren't comments
+        let mut lexer = Lexer::with_alphabet(|c| c.is_alphanumeric() || c == '/' || c == '*');
+        let tokens = lexer.tokenize("image/* & //x # comment").unwrap();
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[2].kind, TokenKind::Identifier("//x".into()));
+
+        let config = LexerConfig::standard().with_line_comment("--");
+        assert!(Lexer::new().with_config(config.clone()).is_ok());
+        assert!(matches!(
+            Lexer::with_alphabet(|c| c.is_alphabetic() || c == '-').with_config(config),
+            Err(LexerConfigError::InvalidComment(start)) if start == "--"
+        ));
+    }
+
+    #[test]
+    fn newlines_can_separate_statements() {
+        let config = LexerConfig::standard().with_newlines(NewlineMode::Separator);
+        let mut lexer = Lexer::new().with_config(config).unwrap();
+        let tokens = lexer.tokenize("p -> q\n\n# comment\n  p\r\nq").unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| &t.kind).collect::<Vec<&TokenKind>>(),
+            vec![
+                &TokenKind::Identifier("p".into()),
+                &TokenKind::Implies,
+                &TokenKind::Identifier("q".into()),
+                &TokenKind::Separator,
+                &TokenKind::Identifier("p".into()),
+                &TokenKind::Separator,
+                &TokenKind::Identifier("q".into()),
+            ]
+        );
+        assert_eq!(tokens[3].span, (6, 18).into());
+    }
+
     #[test]
     fn take_while_returns_zero_if_no_matches() {
         let mut lexer = Lexer::new();
@@ -276,11 +674,33 @@ mod tests {
         };
     }
 
+    #[test]
+    fn alphabets_can_capture_their_environment() {
+        let extra = String::from("$.");
+        let mut lexer = Lexer::with_alphabet(|c| c.is_alphabetic() || extra.contains(c));
+        let tokens = lexer.tokenize("$env.home & $user").unwrap();
+        assert_eq!(tokens[0].kind, TokenKind::Identifier("$env.home".into()));
+        assert_eq!(tokens[2].kind, TokenKind::Identifier("$user".into()));
+    }
+
+    #[test]
+    fn start_chars_outside_of_the_alphabet_result_on_error() {
+        let mut lexer = Lexer::with_alphabets(|c| c.is_alphabetic(), |c| c.is_alphabetic() || c == '#');
+        assert!(lexer.tokenize("a & b#").is_err());
+        match lexer.tokenize("a & #b").unwrap_err() {
+            LexerError::InvalidAlphabet(c, span) => {
+                assert_eq!(c, '#');
+                assert_eq!(span, (4, 5).into());
+            },
+            _ => unreachable!()
+        };
+    }
+
     #[test]
     #[should_panic]
     fn propositions_cant_start_with_numbers() {
         let mut lexer = Lexer::new();
-        if !lexer.tokenize("pqrs").is_ok() { return }
+        if lexer.tokenize("pqrs").is_err() { return }
 
         let mut lexer = Lexer::new();
         let _ = lexer.tokenize("69p").unwrap();
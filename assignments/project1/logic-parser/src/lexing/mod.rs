@@ -1,7 +1,8 @@
-#[macro_use]
-mod helpers;
-
 pub mod token;
 mod lexer;
+mod keywords;
+mod config;
 
 pub use lexer::*;
+pub use keywords::*;
+pub use config::*;
@@ -8,12 +8,27 @@ pub enum TokenKind {
     Or,
     Implies,
     IfAndOnlyIf,
+    Xor,
+    Nand,
+    Nor,
     OpenParen,
-    CloseParen
+    CloseParen,
+    /// End of a statement, see [`NewlineMode::Separator`](super::NewlineMode::Separator)
+    Separator,
+    /// Ends the label of a statement, `P1: p -> q`
+    Colon,
+    /// Introduces the conclusion of an argument, `p, p -> q ⊢ q`
+    Turnstile,
+    /// Source that couldn't be tokenized, see [`Lexer::tokenize_recovering`](super::Lexer::tokenize_recovering)
+    Error
 }
 
 /// An [`Span`] represents a range of characters in the source code
+///
+/// With the `serde` feature it is (de)serialized as a `[start, end]` pair.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "(usize, usize)", into = "(usize, usize)"))]
 pub struct Span {
     pub start: usize,
     pub end: usize
@@ -46,8 +61,15 @@ impl std::fmt::Display for TokenKind {
             TokenKind::Or => write!(f, "Or()"),
             TokenKind::Implies => write!(f, "Implies()"),
             TokenKind::IfAndOnlyIf => write!(f, "IfAndOnlyIf()"),
+            TokenKind::Xor => write!(f, "Xor()"),
+            TokenKind::Nand => write!(f, "Nand()"),
+            TokenKind::Nor => write!(f, "Nor()"),
             TokenKind::OpenParen => write!(f, "OpenParen()"),
             TokenKind::CloseParen => write!(f, "CloseParen()"),
+            TokenKind::Separator => write!(f, "Separator()"),
+            TokenKind::Colon => write!(f, "Colon()"),
+            TokenKind::Turnstile => write!(f, "Turnstile()"),
+            TokenKind::Error => write!(f, "Error()"),
         }
     }
 }
@@ -57,3 +79,9 @@ impl From<(usize, usize)> for Span {
         Span { start: value.0, end: value.1 }
     }
 }
+
+impl From<Span> for (usize, usize) {
+    fn from(span: Span) -> Self {
+        (span.start, span.end)
+    }
+}
@@ -1,6 +1,13 @@
 pub mod errors;
 pub mod lexing;
 pub mod parsing;
+pub mod evaluating;
+pub mod transforming;
+pub mod solving;
+pub mod dimacs;
+pub mod bdd;
+pub mod minimizing;
+pub mod diagnostics;
 
 #[cfg(feature = "svg-generation")]
 pub mod svg_generation;
@@ -0,0 +1,93 @@
+use crate::parsing::ASTNode;
+
+/// A product of literals over a fixed list of identifiers, written as a cube
+/// like `1-0` (`a ∧ ¬c` over `a`, `b`, `c`).
+///
+/// Identifiers are numbered from the most significant bit, so the first
+/// identifier of `n` is bit `n - 1` of a minterm, as in the rows of a
+/// [`TruthTable`](crate::evaluating::TruthTable).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Implicant {
+    /// Values of the bits that are not eliminated
+    pub value: u64,
+    /// Bits that are eliminated (`-`) are set
+    pub mask: u64,
+    /// Number of identifiers
+    pub width: u32
+}
+
+impl Implicant {
+    pub fn from_minterm(minterm: u64, width: u32) -> Self {
+        Implicant { value: minterm, mask: 0, width }
+    }
+
+    /// Whether the implicant is true for the given minterm.
+    pub fn covers(&self, minterm: u64) -> bool {
+        minterm & !self.mask == self.value
+    }
+
+    /// Number of literals of the product.
+    pub fn literal_count(&self) -> u32 {
+        self.width - self.mask.count_ones()
+    }
+
+    /// Merges two implicants that differ in a single bit, like `10-` and `11-`
+    /// into `1--`.
+    pub fn combine(&self, other: &Implicant) -> Option<Implicant> {
+        let difference = self.value ^ other.value;
+        if self.mask != other.mask || difference.count_ones() != 1 {
+            return None;
+        }
+        Some(Implicant {
+            value: self.value & !difference,
+            mask: self.mask | difference,
+            width: self.width
+        })
+    }
+
+    /// Value of the identifier at `index`, or `None` if it is eliminated.
+    pub fn literal(&self, index: u32) -> Option<bool> {
+        let bit = 1 << (self.width - 1 - index);
+        if self.mask & bit != 0 { None } else { Some(self.value & bit != 0) }
+    }
+
+    /// Cube notation: `1`, `0` or `-` for each identifier.
+    pub fn as_cube(&self) -> String {
+        (0..self.width).map(|i| match self.literal(i) {
+            Some(true) => '1',
+            Some(false) => '0',
+            None => '-'
+        }).collect()
+    }
+
+    /// Builds the conjunction of the literals, `true` if there are none.
+    pub fn to_ast<S: AsRef<str>>(&self, identifiers: &[S]) -> ASTNode {
+        (0..self.width)
+            .filter_map(|i| self.literal(i).map(|value| {
+                let identifier = ASTNode::Identifier { name: identifiers[i as usize].as_ref().to_owned(), span: None };
+                if value { identifier } else { ASTNode::Not { operand: Box::new(identifier), span: None } }
+            }))
+            .reduce(|l, r| ASTNode::And { left: Box::new(l), right: Box::new(r), span: None })
+            .unwrap_or(ASTNode::Literal { value: true, span: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn implicants_are_combined() {
+        let a = Implicant::from_minterm(0b100, 3);
+        let b = Implicant::from_minterm(0b110, 3);
+        let ab = a.combine(&b).unwrap();
+
+        assert_eq!(ab.as_cube(), "1-0");
+        assert!(ab.covers(0b100) && ab.covers(0b110) && !ab.covers(0b101));
+        assert_eq!(ab.literal_count(), 2);
+        assert_eq!(ab.to_ast(&["a", "b", "c"]).to_string(), "a & ~c");
+        assert!(a.combine(&Implicant::from_minterm(0b111, 3)).is_none());
+        assert!(ab.combine(&a).is_none());
+    }
+}
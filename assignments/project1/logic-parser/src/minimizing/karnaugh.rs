@@ -0,0 +1,216 @@
+use crate::errors::KarnaughError;
+use crate::parsing::ASTNode;
+use super::implicant::Implicant;
+use super::quine_mccluskey::minimize;
+
+pub type Result<T> = std::result::Result<T, KarnaughError>;
+
+/// Smallest and biggest number of identifiers of a [`KarnaughMap`].
+pub const KARNAUGH_IDENTIFIERS: std::ops::RangeInclusive<usize> = 2..=6;
+
+/// Rectangle of cells covered by an implicant. The rows and columns may
+/// wrap around the edges of the map.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KarnaughGroup {
+    pub implicant: Implicant,
+    /// Indices of the covered rows, in map order
+    pub rows: Vec<usize>,
+    /// Indices of the covered columns, in map order
+    pub columns: Vec<usize>
+}
+
+/// Karnaugh map of an expression with 2 to 6 identifiers.
+///
+/// The first half of the identifiers (rounded down) labels the rows and the
+/// rest label the columns. Both are ordered in Gray code, so neighbouring
+/// cells, including the ones on opposite edges, differ in a single
+/// identifier.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KarnaughMap {
+    pub identifiers: Vec<String>,
+    /// Number of identifiers labeling the rows
+    pub row_bits: u32,
+    /// Number of identifiers labeling the columns
+    pub column_bits: u32,
+    /// `cells[row][column]`
+    pub cells: Vec<Vec<bool>>,
+    pub groups: Vec<KarnaughGroup>
+}
+
+/// Reflected binary code of `n`.
+pub fn gray_code(n: u64) -> u64 {
+    n ^ (n >> 1)
+}
+
+impl KarnaughMap {
+    /// Evaluates the expression on every cell, over its identifiers in order
+    /// of first appearance.
+    pub fn new(ast: &ASTNode) -> Result<Self> {
+        let identifiers = ast.get_ordered_identifiers();
+        Self::with_order(ast, &identifiers)
+    }
+
+    /// Like [`KarnaughMap::new`] but over the given identifiers, which must
+    /// include the ones of the expression or an
+    /// [`EvaluationError`](crate::errors::EvaluationError) is returned.
+    pub fn with_order<S: AsRef<str>>(ast: &ASTNode, identifiers: &[S]) -> Result<Self> {
+        if !KARNAUGH_IDENTIFIERS.contains(&identifiers.len()) {
+            return Err(KarnaughError::UnsupportedIdentifierCount(identifiers.len()));
+        }
+        let identifiers: Vec<String> = identifiers.iter().map(|i| i.as_ref().to_owned()).collect();
+        let n = identifiers.len() as u32;
+        let row_bits = n / 2;
+        let column_bits = n - row_bits;
+
+        let mut map = KarnaughMap { identifiers, row_bits, column_bits, cells: vec![], groups: vec![] };
+        map.cells = (0..map.row_count())
+            .map(|row| (0..map.column_count()).map(|column| {
+                let minterm = map.minterm(row, column);
+                ast.evaluate_with(|name| {
+                    let index = map.identifiers.iter().position(|i| i == name)?;
+                    Some(minterm & (1 << (n as usize - 1 - index)) != 0)
+                })
+            }).collect::<std::result::Result<_, _>>())
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(map)
+    }
+
+    /// Adds the groups of a minimal sum of products of the map.
+    pub fn with_groups(mut self) -> Self {
+        let minterms: Vec<u64> = self.minterms().collect();
+        let minimization = minimize(&self.identifiers, &minterms, &[])
+            .expect("karnaugh maps are small enough to be minimized");
+        self.groups = minimization.implicants().into_iter().map(|p| self.group(p)).collect();
+        self
+    }
+
+    pub fn row_count(&self) -> usize {
+        1 << self.row_bits
+    }
+
+    pub fn column_count(&self) -> usize {
+        1 << self.column_bits
+    }
+
+    /// Minterm of a cell, with the first identifier as the most significant
+    /// bit.
+    pub fn minterm(&self, row: usize, column: usize) -> u64 {
+        gray_code(row as u64) << self.column_bits | gray_code(column as u64)
+    }
+
+    /// Minterms of the cells that are true.
+    pub fn minterms(&self) -> impl Iterator<Item = u64> + '_ {
+        self.cells.iter().enumerate().flat_map(move |(row, cells)| {
+            cells.iter().enumerate().filter(|(_, v)| **v).map(move |(column, _)| self.minterm(row, column))
+        })
+    }
+
+    /// Gray code labels of the rows, like `["00", "01", "11", "10"]`.
+    pub fn row_labels(&self) -> Vec<String> {
+        (0..self.row_count()).map(|r| format!("{:0w$b}", gray_code(r as u64), w = self.row_bits as usize)).collect()
+    }
+
+    /// Gray code labels of the columns.
+    pub fn column_labels(&self) -> Vec<String> {
+        (0..self.column_count()).map(|c| format!("{:0w$b}", gray_code(c as u64), w = self.column_bits as usize)).collect()
+    }
+
+    /// Cells covered by an implicant over the identifiers of the map.
+    pub fn group(&self, implicant: Implicant) -> KarnaughGroup {
+        let column_mask = (1 << self.column_bits) - 1;
+        KarnaughGroup {
+            implicant,
+            rows: (0..self.row_count())
+                .filter(|r| implicant.covers(self.minterm(*r, 0) & !column_mask | implicant.value & column_mask))
+                .collect(),
+            columns: (0..self.column_count())
+                .filter(|c| implicant.covers(self.minterm(0, *c) & column_mask | implicant.value & !column_mask))
+                .collect()
+        }
+    }
+}
+
+impl KarnaughGroup {
+    /// Splits the group in contiguous rectangles, `(first row, row count,
+    /// first column, column count)`, since groups may wrap around the edges.
+    pub fn rectangles(&self) -> Vec<(usize, usize, usize, usize)> {
+        let rows = runs(&self.rows);
+        let columns = runs(&self.columns);
+        rows.iter()
+            .flat_map(|(r, rl)| columns.iter().map(move |(c, cl)| (*r, *rl, *c, *cl)))
+            .collect()
+    }
+}
+
+/// Contiguous runs of sorted indices, as `(start, length)`.
+fn runs(indices: &[usize]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for index in indices {
+        match runs.last_mut() {
+            Some((start, length)) if *start + *length == *index => *length += 1,
+            _ => runs.push((*index, 1))
+        }
+    }
+    runs
+}
+
+impl ASTNode {
+    /// Karnaugh map of the expression, see [`KarnaughMap::new`].
+    pub fn to_karnaugh_map(&self) -> Result<KarnaughMap> {
+        KarnaughMap::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::EvaluationError;
+    use crate::parsing::testing::parse;
+
+    #[test]
+    fn cells_follow_gray_code() {
+        let map = parse("(a & ~c) | (b & c)").to_karnaugh_map().unwrap();
+
+        assert_eq!((map.row_count(), map.column_count()), (2, 4));
+        assert_eq!(map.row_labels(), vec!["0", "1"]);
+        assert_eq!(map.column_labels(), vec!["00", "01", "11", "10"]);
+        // Identifiers in order of appearance, columns are c b
+        assert_eq!(map.identifiers, vec!["a", "c", "b"]);
+        assert_eq!(map.cells, vec![
+            vec![false, false, true, false],
+            vec![true, true, true, false],
+        ]);
+        assert_eq!(map.minterm(1, 3), 0b110);
+    }
+
+    #[test]
+    fn identifier_count_is_checked() {
+        assert!(parse("p").to_karnaugh_map().is_err());
+        assert!(parse("a & b & c & d & e & f & g").to_karnaugh_map().is_err());
+        assert!(KarnaughMap::with_order(&parse("p"), &["p", "q"]).is_ok());
+        assert!(matches!(
+            KarnaughMap::with_order(&parse("p & r"), &["p", "q"]),
+            Err(KarnaughError::Evaluation(EvaluationError::UnassignedIdentifier(name, _))) if name == "r"
+        ));
+
+        for n in KARNAUGH_IDENTIFIERS {
+            let expression = (0..n).map(|i| format!("x{i}")).collect::<Vec<_>>().join(" | ");
+            let map = parse(&expression).to_karnaugh_map().unwrap();
+            assert_eq!(map.minterms().count(), (1 << n) - 1);
+        }
+    }
+
+    #[test]
+    fn groups_wrap_around() {
+        // Corners of a 4x4 map
+        let map = KarnaughMap::with_order(&parse("~b & ~d"), &["a", "b", "c", "d"]).unwrap().with_groups();
+        assert_eq!(map.groups.len(), 1);
+
+        let group = &map.groups[0];
+        assert_eq!(group.implicant.as_cube(), "-0-0");
+        assert_eq!((group.rows.clone(), group.columns.clone()), (vec![0, 3], vec![0, 3]));
+        assert_eq!(group.rectangles(), vec![(0, 1, 0, 1), (0, 1, 3, 1), (3, 1, 0, 1), (3, 1, 3, 1)]);
+    }
+}
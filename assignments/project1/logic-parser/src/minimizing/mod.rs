@@ -0,0 +1,7 @@
+mod implicant;
+mod quine_mccluskey;
+mod karnaugh;
+
+pub use implicant::*;
+pub use quine_mccluskey::*;
+pub use karnaugh::*;
@@ -0,0 +1,365 @@
+use std::collections::BTreeSet;
+use crate::errors::MinimizationError;
+use crate::parsing::ASTNode;
+use super::implicant::Implicant;
+
+/// Biggest number of identifiers that can be minimized, the `2^n` possible
+/// minterms are enumerated.
+pub const MINIMIZATION_MAX_IDENTIFIERS: usize = 16;
+/// Cyclic cores with more candidate prime implicants than this are covered
+/// greedily instead of with Petrick's method.
+const PETRICK_MAX_PRIMES: usize = 64;
+/// Petrick's method gives up when the expanded sum of products has more
+/// terms than this.
+const PETRICK_MAX_TERMS: usize = 4096;
+
+/// Prime implicant chart: which prime implicants cover each minterm.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrimeImplicantChart {
+    pub identifiers: Vec<String>,
+    /// Minterms that must be covered
+    pub minterms: Vec<u64>,
+    /// Minterms that may be covered or not
+    pub dont_cares: Vec<u64>,
+    pub prime_implicants: Vec<Implicant>
+}
+
+impl PrimeImplicantChart {
+    /// Indices of the prime implicants that cover `minterm`.
+    pub fn covering(&self, minterm: u64) -> Vec<usize> {
+        self.prime_implicants.iter().enumerate()
+            .filter(|(_, p)| p.covers(minterm))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Indices of the prime implicants that are the only ones covering some
+    /// minterm.
+    pub fn essential(&self) -> Vec<usize> {
+        let essential: BTreeSet<usize> = self.minterms.iter()
+            .map(|m| self.covering(*m))
+            .filter(|c| c.len() == 1)
+            .map(|c| c[0])
+            .collect();
+        essential.into_iter().collect()
+    }
+}
+
+/// How the prime implicants of a [`Minimization`] were selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CoverMethod {
+    /// Essential prime implicants were enough
+    Essential,
+    /// Exact cover with Petrick's method, the result is minimal
+    Petrick,
+    /// Greedy cover for big charts, the result may not be minimal
+    Greedy
+}
+
+/// Result of a two-level minimization.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Minimization {
+    pub chart: PrimeImplicantChart,
+    /// Indices of the chart's prime implicants in the result
+    pub selected: Vec<usize>,
+    pub method: CoverMethod,
+    /// Minimal sum of products
+    pub result: ASTNode
+}
+
+impl Minimization {
+    /// The selected prime implicants.
+    pub fn implicants(&self) -> Vec<Implicant> {
+        self.selected.iter().map(|i| self.chart.prime_implicants[*i]).collect()
+    }
+}
+
+/// Finds a minimal sum of products that is true for every minterm in
+/// `minterms` and false outside of them and `dont_cares`.
+///
+/// Prime implicants are generated with the Quine–McCluskey algorithm. After
+/// taking the essential ones, the remaining minterms are covered with
+/// Petrick's method, or greedily when the chart is too big.
+///
+/// ```
+/// use logic_parser::minimizing::minimize;
+///
+/// // Σm(4, 8, 10, 11, 12, 15) + d(9, 14)
+/// let minimization = minimize(&["a", "b", "c", "d"], &[4, 8, 10, 11, 12, 15], &[9, 14]).unwrap();
+///
+/// assert_eq!(minimization.result.to_string(), "a & c | a & ~b | b & ~c & ~d");
+/// ```
+///
+/// Fails if there are more than [`MINIMIZATION_MAX_IDENTIFIERS`] identifiers,
+/// or if a minterm doesn't fit in them.
+pub fn minimize<S: AsRef<str>>(identifiers: &[S], minterms: &[u64], dont_cares: &[u64]) -> Result<Minimization, MinimizationError> {
+    if identifiers.len() > MINIMIZATION_MAX_IDENTIFIERS {
+        return Err(MinimizationError::TooManyIdentifiers(identifiers.len()));
+    }
+    let width = identifiers.len() as u32;
+    if let Some(m) = minterms.iter().chain(dont_cares).find(|m| **m >> width != 0) {
+        return Err(MinimizationError::MintermOutOfRange(*m, identifiers.len()));
+    }
+    let identifiers: Vec<String> = identifiers.iter().map(|i| i.as_ref().to_owned()).collect();
+
+    let minterms: BTreeSet<u64> = minterms.iter().copied().collect();
+    let dont_cares: BTreeSet<u64> = dont_cares.iter().copied().filter(|d| !minterms.contains(d)).collect();
+
+    let chart = PrimeImplicantChart {
+        prime_implicants: prime_implicants(minterms.union(&dont_cares).copied(), width),
+        minterms: minterms.into_iter().collect(),
+        dont_cares: dont_cares.into_iter().collect(),
+        identifiers
+    };
+
+    let mut selected = chart.essential();
+    let uncovered: Vec<u64> = chart.minterms.iter().copied()
+        .filter(|m| !selected.iter().any(|i| chart.prime_implicants[*i].covers(*m)))
+        .collect();
+
+    let method = if uncovered.is_empty() {
+        CoverMethod::Essential
+    }
+    else {
+        match petrick(&chart, &uncovered) {
+            Some(cover) => {
+                selected.extend(cover);
+                CoverMethod::Petrick
+            },
+            None => {
+                selected.extend(greedy(&chart, uncovered));
+                CoverMethod::Greedy
+            }
+        }
+    };
+    selected.sort();
+
+    let result = selected.iter()
+        .map(|i| chart.prime_implicants[*i].to_ast(&chart.identifiers))
+        .reduce(|l, r| ASTNode::Or { left: Box::new(l), right: Box::new(r), span: None })
+        .unwrap_or(ASTNode::Literal { value: false, span: None });
+
+    Ok(Minimization { chart, selected, method, result })
+}
+
+impl ASTNode {
+    /// Minimal sum of products of the expression, over its identifiers in
+    /// order of first appearance. See [`minimize`].
+    pub fn minimize(&self) -> Result<Minimization, MinimizationError> {
+        self.minimize_with_dont_cares(&ASTNode::Literal { value: false, span: None })
+    }
+
+    /// Like [`ASTNode::minimize`], but the assignments that make `dont_cares`
+    /// true can take any value.
+    pub fn minimize_with_dont_cares(&self, dont_cares: &ASTNode) -> Result<Minimization, MinimizationError> {
+        let mut identifiers = self.get_ordered_identifiers();
+        for identifier in dont_cares.get_ordered_identifiers() {
+            if !identifiers.contains(&identifier) {
+                identifiers.push(identifier);
+            }
+        }
+        if identifiers.len() > MINIMIZATION_MAX_IDENTIFIERS {
+            return Err(MinimizationError::TooManyIdentifiers(identifiers.len()));
+        }
+
+        let n = identifiers.len();
+        let (mut ones, mut dcs) = (vec![], vec![]);
+        for minterm in 0..1_u64 << n {
+            let resolve = |name: &str| {
+                identifiers.iter().position(|i| *i == name).map(|i| minterm & (1 << (n - 1 - i)) != 0)
+            };
+            if dont_cares.evaluate_with(resolve)? {
+                dcs.push(minterm);
+            }
+            else if self.evaluate_with(resolve)? {
+                ones.push(minterm);
+            }
+        }
+        minimize(&identifiers, &ones, &dcs)
+    }
+}
+
+fn prime_implicants(minterms: impl Iterator<Item = u64>, width: u32) -> Vec<Implicant> {
+    let mut current: BTreeSet<Implicant> = minterms.map(|m| Implicant::from_minterm(m, width)).collect();
+    let mut primes: BTreeSet<Implicant> = BTreeSet::new();
+
+    while !current.is_empty() {
+        let implicants: Vec<Implicant> = current.iter().copied().collect();
+        let mut combined = vec![false; implicants.len()];
+        let mut next = BTreeSet::new();
+
+        for i in 0..implicants.len() {
+            for j in (i + 1)..implicants.len() {
+                if let Some(merged) = implicants[i].combine(&implicants[j]) {
+                    next.insert(merged);
+                    combined[i] = true;
+                    combined[j] = true;
+                }
+            }
+        }
+        primes.extend(implicants.iter().zip(combined).filter(|(_, c)| !c).map(|(p, _)| *p));
+        current = next;
+    }
+
+    // Bigger implicants first
+    let mut primes: Vec<Implicant> = primes.into_iter().collect();
+    primes.sort_by_key(|p| (p.literal_count(), std::cmp::Reverse(p.value)));
+    primes
+}
+
+/// Exact cover of `uncovered` using Petrick's method. Among the covers with
+/// fewer implicants, the one with fewer literals is chosen.
+fn petrick(chart: &PrimeImplicantChart, uncovered: &[u64]) -> Option<Vec<usize>> {
+    let candidates: Vec<usize> = (0..chart.prime_implicants.len())
+        .filter(|i| uncovered.iter().any(|m| chart.prime_implicants[*i].covers(*m)))
+        .collect();
+    if candidates.len() > PETRICK_MAX_PRIMES {
+        return None;
+    }
+
+    // Each product is a bitset over `candidates`
+    let mut sum: Vec<u64> = vec![0];
+    for minterm in uncovered {
+        let factor: Vec<u64> = candidates.iter().enumerate()
+            .filter(|(_, p)| chart.prime_implicants[**p].covers(*minterm))
+            .map(|(bit, _)| 1 << bit)
+            .collect();
+
+        let mut expanded: Vec<u64> = Vec::new();
+        for product in &sum {
+            for f in &factor {
+                expanded.push(product | f);
+            }
+        }
+        // Absorption: X + XY = X
+        expanded.sort_by_key(|p| p.count_ones());
+        expanded.dedup();
+        let mut absorbed: Vec<u64> = Vec::new();
+        for product in expanded {
+            if !absorbed.iter().any(|a| a & product == *a) {
+                absorbed.push(product);
+            }
+        }
+        if absorbed.len() > PETRICK_MAX_TERMS {
+            return None;
+        }
+        sum = absorbed;
+    }
+
+    let literals = |product: u64| -> u32 {
+        candidates.iter().enumerate()
+            .filter(|(bit, _)| product & (1 << bit) != 0)
+            .map(|(_, p)| chart.prime_implicants[*p].literal_count())
+            .sum()
+    };
+    let best = sum.into_iter().min_by_key(|p| (p.count_ones(), literals(*p)))?;
+
+    Some(
+        candidates.iter().enumerate()
+            .filter(|(bit, _)| best & (1 << bit) != 0)
+            .map(|(_, p)| *p)
+            .collect()
+    )
+}
+
+/// Repeatedly picks the prime implicant that covers the most uncovered
+/// minterms, preferring the ones with fewer literals.
+fn greedy(chart: &PrimeImplicantChart, mut uncovered: Vec<u64>) -> Vec<usize> {
+    let mut selected = Vec::new();
+    while !uncovered.is_empty() {
+        let (best, _) = chart.prime_implicants.iter().enumerate()
+            .map(|(i, p)| (i, uncovered.iter().filter(|m| p.covers(**m)).count()))
+            .max_by_key(|(i, count)| (*count, std::cmp::Reverse(chart.prime_implicants[*i].literal_count())))
+            .expect("every minterm is covered by some prime implicant");
+        uncovered.retain(|m| !chart.prime_implicants[best].covers(*m));
+        selected.push(best);
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::EvaluationError;
+    use crate::parsing::testing::{parse, parse_recovered};
+
+    #[test]
+    fn prime_implicants_are_found() {
+        let minimization = minimize(&["a", "b", "c", "d"], &[4, 8, 10, 11, 12, 15], &[9, 14]).unwrap();
+        let mut cubes: Vec<String> = minimization.chart.prime_implicants.iter().map(|p| p.as_cube()).collect();
+        cubes.sort();
+
+        assert_eq!(cubes, vec!["-100", "1--0", "1-1-", "10--"]);
+        assert_eq!(minimization.chart.essential().len(), 2);
+    }
+
+    #[test]
+    fn minimization_is_equivalent_and_minimal() {
+        let cases = [
+            ("(a & b) | (a & ~b)", "a"),
+            ("(p -> q) & (q -> p)", "p & q | ~p & ~q"),
+            ("a & b & c | a & b & ~c | a & ~b & c", "a & b | a & c"),
+            ("p & ~p", "false"),
+            ("p | ~p", "true"),
+        ];
+        for (expression, expected) in cases {
+            let ast = parse(expression);
+            let minimization = ast.minimize().unwrap();
+            assert_eq!(minimization.result.to_string(), expected, "{expression}");
+            assert!(minimization.result.is_equivalent_to(&ast).unwrap());
+        }
+    }
+
+    #[test]
+    fn cyclic_charts_are_solved_with_petrick() {
+        // Σm(0, 1, 2, 5, 6, 7) has no essential prime implicants
+        let minimization = minimize(&["a", "b", "c"], &[0, 1, 2, 5, 6, 7], &[]).unwrap();
+        assert!(minimization.chart.essential().is_empty());
+        assert_eq!(minimization.method, CoverMethod::Petrick);
+        assert_eq!(minimization.selected.len(), 3);
+    }
+
+    #[test]
+    fn dont_cares_are_used() {
+        // BCD digits greater than 4, the codes 10-15 never happen
+        let ast = parse("(a & ~b & ~c & ~d) | (~a & b & c) | (~a & b & ~c & d) | (a & ~b & ~c & d)");
+        let dont_cares = parse("a & (b | c)");
+        let minimization = ast.minimize_with_dont_cares(&dont_cares).unwrap();
+
+        assert_eq!(minimization.result.to_string(), "a | b & c | b & d");
+        assert_eq!(minimization.chart.dont_cares.len(), 6);
+    }
+
+    #[test]
+    fn big_charts_fall_back_to_greedy() {
+        // Parity has no adjacent minterms, every minterm is an essential prime
+        let parity = minimize(&["a", "b", "c", "d", "e", "f"], &(0..64).filter(|m: &u64| m.count_ones() % 2 == 1).collect::<Vec<_>>(), &[]).unwrap();
+        assert_eq!(parity.method, CoverMethod::Essential);
+        assert_eq!(parity.selected.len(), 32);
+
+        let identifiers: Vec<String> = (0..8).map(|i| format!("x{i}")).collect();
+        let minterms: Vec<u64> = (0..256).filter(|m: &u64| m.count_ones() == 4 || m.count_ones() == 5).collect();
+        let minimization = minimize(&identifiers, &minterms, &[]).unwrap();
+        assert_eq!(minimization.method, CoverMethod::Greedy);
+        assert!(minterms.iter().all(|m| minimization.implicants().iter().any(|p| p.covers(*m))));
+    }
+
+    #[test]
+    fn out_of_range_inputs_result_on_error() {
+        assert!(matches!(minimize(&["a", "b"], &[1, 4], &[]), Err(MinimizationError::MintermOutOfRange(4, 2))));
+        assert!(matches!(minimize(&["a"], &[0], &[2]), Err(MinimizationError::MintermOutOfRange(2, 1))));
+
+        let identifiers: Vec<String> = (0..64).map(|i| format!("x{i}")).collect();
+        assert!(matches!(minimize(&identifiers, &[1], &[]), Err(MinimizationError::TooManyIdentifiers(64))));
+
+        let expression = (0..17).map(|i| format!("x{i}")).collect::<Vec<_>>().join(" | ");
+        assert!(matches!(parse(&expression).minimize(), Err(MinimizationError::TooManyIdentifiers(17))));
+
+        let ast = parse_recovered("p | $");
+        assert!(matches!(ast.minimize(), Err(MinimizationError::Evaluation(EvaluationError::ErrorNode(_)))));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::lexing::token::Span;
+use super::node::{ASTNode, write_identifier};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+#[cfg(not(feature = "serde"))]
+use crate::diagnostics::json_string;
+
+/// A formula of a [`Document`], optionally labelled like `P1: p -> q`.
+///
+/// Like in [`ASTNode`], spans are ignored when comparing statements.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Statement {
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub label: Option<String>,
+    pub formula: ASTNode,
+    /// From the label, if any, to the end of the formula
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub span: Option<Span>
+}
+
+/// Many statements read by [`Parser::parse_document`](super::Parser::parse_document):
+/// the premises of an argument and, after a turnstile, its conclusion.
+///
+/// ```yaml
+/// document: [statement] (; [statement])* [|- statement (;)*]
+/// statement: [label :] expr
+/// ```
+///
+/// ```
+/// use logic_parser::lexing::Lexer;
+/// use logic_parser::parsing::Parser;
+///
+/// let tokens = Lexer::new().tokenize("P1: p -> q; P2: q -> r; p |- r").unwrap();
+/// let document = Parser::new(&tokens).parse_document().unwrap();
+///
+/// assert_eq!(document.premises.len(), 3);
+/// assert_eq!(document.premises[1].label.as_deref(), Some("P2"));
+/// assert_eq!(document.conclusion.unwrap().formula.to_string(), "r");
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Document {
+    pub premises: Vec<Statement>,
+    pub conclusion: Option<Statement>
+}
+
+impl Statement {
+    #[cfg(not(feature = "serde"))]
+    pub fn as_json(&self) -> String {
+        let label = self.label.as_ref()
+            .map(|l| format!(r#""label": {},"#, json_string(l)))
+            .unwrap_or_default();
+        let span = self.span
+            .map(|s| format!(r#",
+                "span": [{}, {}]"#, s.start, s.end))
+            .unwrap_or_default();
+
+        format!(r###"{{
+                {label}
+                "formula": {formula}{span}
+            }}"###, formula=self.formula.as_json())
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn as_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+impl Document {
+    #[cfg(not(feature = "serde"))]
+    pub fn as_json(&self) -> String {
+        format!(r###"{{
+            "premises": [{premises}],
+            "conclusion": {conclusion}
+        }}"###,
+            premises = self.premises.iter().map(Statement::as_json).collect::<Vec<String>>().join(", "),
+            conclusion = self.conclusion.as_ref().map(Statement::as_json).unwrap_or("null".into())
+        )
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn as_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+impl PartialEq for Statement {
+    fn eq(&self, other: &Self) -> bool {
+        self.label == other.label && self.formula == other.formula
+    }
+}
+
+impl std::fmt::Display for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(label) = &self.label {
+            write_identifier(f, label)?;
+            write!(f, ": ")?;
+        }
+        write!(f, "{}", self.formula)
+    }
+}
+
+/// Formats the document on a single line that can be parsed back, like
+/// `P1: p -> q; p |- q`.
+impl std::fmt::Display for Document {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, premise) in self.premises.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{premise}")?;
+        }
+        match &self.conclusion {
+            Some(conclusion) if self.premises.is_empty() => write!(f, "|- {conclusion}"),
+            Some(conclusion) => write!(f, " |- {conclusion}"),
+            None => Ok(())
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::lexing::token::TokenKind;
+
+/// A token the [`Parser`](super::Parser) would have accepted where it failed,
+/// reported by [`ParserError`](crate::errors::ParserError) so frontends can
+/// offer completions.
+///
+/// ```
+/// use logic_parser::lexing::{Lexer, token::TokenKind};
+/// use logic_parser::parsing::{Parser, Expected};
+///
+/// let tokens = Lexer::new().tokenize("p &").unwrap();
+/// let error = Parser::new(&tokens).parse().unwrap_err();
+///
+/// assert_eq!(error.expected(), [
+///     Expected::Identifier,
+///     Expected::Literal,
+///     Expected::Token(TokenKind::Not),
+///     Expected::Token(TokenKind::OpenParen)
+/// ]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    Identifier,
+    Literal,
+    /// A token without value, like an operator or a parenthesis
+    Token(TokenKind),
+    EndOfInput
+}
+
+impl Expected {
+    /// Tokens that can start an operand.
+    pub(crate) fn operand() -> Vec<Expected> {
+        vec![
+            Expected::Identifier,
+            Expected::Literal,
+            Expected::Token(TokenKind::Not),
+            Expected::Token(TokenKind::OpenParen)
+        ]
+    }
+}
+
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spelling = match self {
+            Expected::Identifier => return write!(f, "identifier"),
+            Expected::Literal => return write!(f, "literal"),
+            Expected::EndOfInput => return write!(f, "end of input"),
+            Expected::Token(kind) => match kind {
+                TokenKind::Not => "~",
+                TokenKind::And => "&",
+                TokenKind::Or => "|",
+                TokenKind::Implies => "->",
+                TokenKind::IfAndOnlyIf => "<->",
+                TokenKind::Xor => "^",
+                TokenKind::Nand => "nand",
+                TokenKind::Nor => "nor",
+                TokenKind::OpenParen => "(",
+                TokenKind::CloseParen => ")",
+                TokenKind::Separator => ";",
+                TokenKind::Colon => ":",
+                TokenKind::Turnstile => "|-",
+                other => return write!(f, "{other}")
+            }
+        };
+        write!(f, "'{spelling}'")
+    }
+}
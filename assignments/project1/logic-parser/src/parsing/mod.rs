@@ -1,5 +1,13 @@
 mod parser;
 mod node;
+mod precedence;
+mod expected;
+mod document;
+#[cfg(test)]
+pub(crate) mod testing;
 
 pub use parser::*;
 pub use node::*;
+pub use precedence::*;
+pub use expected::*;
+pub use document::*;
@@ -1,55 +1,228 @@
 use std::collections::HashSet;
+use crate::lexing::{Keywords, DEFAULT_ALPHABET, DEFAULT_START_ALPHABET};
+use crate::lexing::token::Span;
 
 #[cfg(feature = "serde")]
 use serde::{Serialize, Deserialize};
+#[cfg(not(feature = "serde"))]
+use crate::diagnostics::json_string;
 
 #[cfg(feature = "serde")]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all="snake_case")]
 pub enum ASTNode {
-    Identifier { name: String },
-    Literal { value: bool },
+    Identifier {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
+    Literal {
+        value: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
     #[serde(rename = "operator.not")]
-    Not { operand: Box<ASTNode> },
+    Not {
+        operand: Box<ASTNode>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
     #[serde(rename = "operator.and")]
-    And { left: Box<ASTNode>, right: Box<ASTNode> },
+    And {
+        left: Box<ASTNode>,
+        right: Box<ASTNode>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
     #[serde(rename = "operator.or")]
-    Or { left: Box<ASTNode>, right: Box<ASTNode> },
+    Or {
+        left: Box<ASTNode>,
+        right: Box<ASTNode>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
     #[serde(rename = "operator.implies")]
-    Implies { left: Box<ASTNode>, right: Box<ASTNode> },
+    Implies {
+        left: Box<ASTNode>,
+        right: Box<ASTNode>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
     #[serde(rename = "operator.iff")]
-    IfAndOnlyIf { left: Box<ASTNode>, right: Box<ASTNode> },
+    IfAndOnlyIf {
+        left: Box<ASTNode>,
+        right: Box<ASTNode>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
+    #[serde(rename = "operator.xor")]
+    Xor {
+        left: Box<ASTNode>,
+        right: Box<ASTNode>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
+    #[serde(rename = "operator.nand")]
+    Nand {
+        left: Box<ASTNode>,
+        right: Box<ASTNode>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
+    #[serde(rename = "operator.nor")]
+    Nor {
+        left: Box<ASTNode>,
+        right: Box<ASTNode>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
+    /// Part of the source that couldn't be parsed, see [`Parser::parse_recovering`](super::Parser::parse_recovering).
+    /// It has no truth value: evaluating it, classifying it, building truth
+    /// tables, Karnaugh maps, normal forms, BDDs or the Tseitin encoding and
+    /// minimizing fail.
+    Error {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<Span>
+    },
 }
 
 // Serde Serialize and Deserialize traits are available when the
 // optional 'serde' feature is enabled
 
 #[cfg(not(feature = "serde"))]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ASTNode {
-    Identifier { name: String },
-    Literal { value: bool },
-    Not { operand: Box<ASTNode> },
-    And { left: Box<ASTNode>, right: Box<ASTNode> },
-    Or { left: Box<ASTNode>, right: Box<ASTNode> },
-    Implies { left: Box<ASTNode>, right: Box<ASTNode> },
-    IfAndOnlyIf { left: Box<ASTNode>, right: Box<ASTNode> },
+    Identifier { name: String, span: Option<Span> },
+    Literal { value: bool, span: Option<Span> },
+    Not { operand: Box<ASTNode>, span: Option<Span> },
+    And { left: Box<ASTNode>, right: Box<ASTNode>, span: Option<Span> },
+    Or { left: Box<ASTNode>, right: Box<ASTNode>, span: Option<Span> },
+    Implies { left: Box<ASTNode>, right: Box<ASTNode>, span: Option<Span> },
+    IfAndOnlyIf { left: Box<ASTNode>, right: Box<ASTNode>, span: Option<Span> },
+    Xor { left: Box<ASTNode>, right: Box<ASTNode>, span: Option<Span> },
+    Nand { left: Box<ASTNode>, right: Box<ASTNode>, span: Option<Span> },
+    Nor { left: Box<ASTNode>, right: Box<ASTNode>, span: Option<Span> },
+    /// Part of the source that couldn't be parsed, see [`Parser::parse_recovering`](super::Parser::parse_recovering).
+    /// It has no truth value: evaluating it, classifying it, building truth
+    /// tables, Karnaugh maps, normal forms, BDDs or the Tseitin encoding and
+    /// minimizing fail.
+    Error { span: Option<Span> },
+}
+
+/// Nodes are compared structurally, their spans are ignored: `p & q` parsed
+/// from `(p) & q` is equal to the one built by hand. Error nodes are never
+/// equal, not even to themselves, since nothing is known about them.
+impl PartialEq for ASTNode {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ASTNode::Identifier { name: a, .. }, ASTNode::Identifier { name: b, .. }) => a == b,
+            (ASTNode::Literal { value: a, .. }, ASTNode::Literal { value: b, .. }) => a == b,
+            (ASTNode::Not { operand: a, .. }, ASTNode::Not { operand: b, .. }) => a == b,
+            (ASTNode::And { left: l1, right: r1, .. }, ASTNode::And { left: l2, right: r2, .. }) |
+            (ASTNode::Or { left: l1, right: r1, .. }, ASTNode::Or { left: l2, right: r2, .. }) |
+            (ASTNode::Implies { left: l1, right: r1, .. }, ASTNode::Implies { left: l2, right: r2, .. }) |
+            (ASTNode::IfAndOnlyIf { left: l1, right: r1, .. }, ASTNode::IfAndOnlyIf { left: l2, right: r2, .. }) |
+            (ASTNode::Xor { left: l1, right: r1, .. }, ASTNode::Xor { left: l2, right: r2, .. }) |
+            (ASTNode::Nand { left: l1, right: r1, .. }, ASTNode::Nand { left: l2, right: r2, .. }) |
+            (ASTNode::Nor { left: l1, right: r1, .. }, ASTNode::Nor { left: l2, right: r2, .. }) => {
+                l1 == l2 && r1 == r2
+            },
+            _ => false
+        }
+    }
 }
 
 impl ASTNode {
+    /// Range of the source code the node was parsed from, including the
+    /// parentheses around it. Nodes built by hand or by a transformation
+    /// have no span.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ASTNode::Identifier { span, .. } |
+            ASTNode::Literal { span, .. } |
+            ASTNode::Not { span, .. } |
+            ASTNode::And { span, .. } |
+            ASTNode::Or { span, .. } |
+            ASTNode::Implies { span, .. } |
+            ASTNode::IfAndOnlyIf { span, .. } |
+            ASTNode::Xor { span, .. } |
+            ASTNode::Nand { span, .. } |
+            ASTNode::Nor { span, .. } |
+            ASTNode::Error { span } => *span
+        }
+    }
+
+    /// Replaces the span of the node, its children are left untouched.
+    pub fn with_span(mut self, new_span: Option<Span>) -> Self {
+        match &mut self {
+            ASTNode::Identifier { span, .. } |
+            ASTNode::Literal { span, .. } |
+            ASTNode::Not { span, .. } |
+            ASTNode::And { span, .. } |
+            ASTNode::Or { span, .. } |
+            ASTNode::Implies { span, .. } |
+            ASTNode::IfAndOnlyIf { span, .. } |
+            ASTNode::Xor { span, .. } |
+            ASTNode::Nand { span, .. } |
+            ASTNode::Nor { span, .. } |
+            ASTNode::Error { span } => *span = new_span
+        }
+        self
+    }
+
+    /// The [`ASTNode::Error`] nodes of the tree, from left to right.
+    pub fn errors(&self) -> Vec<&ASTNode> {
+        match self {
+            ASTNode::Error { .. } => vec![self],
+            ASTNode::Identifier { .. } | ASTNode::Literal { .. } => vec![],
+            ASTNode::Not { operand, .. } => operand.errors(),
+            ASTNode::And { left, right, .. } |
+            ASTNode::Or { left, right, .. } |
+            ASTNode::Implies { left, right, .. } |
+            ASTNode::IfAndOnlyIf { left, right, .. } |
+            ASTNode::Xor { left, right, .. } |
+            ASTNode::Nand { left, right, .. } |
+            ASTNode::Nor { left, right, .. } => {
+                let mut errors = left.errors();
+                errors.extend(right.errors());
+                errors
+            }
+        }
+    }
+
+    /// Whether the tree contains [`ASTNode::Error`] nodes.
+    pub fn has_errors(&self) -> bool {
+        match self {
+            ASTNode::Error { .. } => true,
+            ASTNode::Identifier { .. } | ASTNode::Literal { .. } => false,
+            ASTNode::Not { operand, .. } => operand.has_errors(),
+            ASTNode::And { left, right, .. } |
+            ASTNode::Or { left, right, .. } |
+            ASTNode::Implies { left, right, .. } |
+            ASTNode::IfAndOnlyIf { left, right, .. } |
+            ASTNode::Xor { left, right, .. } |
+            ASTNode::Nand { left, right, .. } |
+            ASTNode::Nor { left, right, .. } => left.has_errors() || right.has_errors()
+        }
+    }
+
     pub fn as_string(&self) -> String {
         format!("{:#?}", self)
     }
 
     pub fn repr(&self) -> &str {
         match self {
-            ASTNode::Identifier { name } => name,
-            ASTNode::Literal { value } => if *value { "1" } else { "0" },
+            ASTNode::Identifier { name, .. } => name,
+            ASTNode::Literal { value, .. } => if *value { "1" } else { "0" },
             ASTNode::Not { .. } => "¬",
             ASTNode::And { .. } => "∧",
             ASTNode::Or { .. } => "∨",
             ASTNode::Implies { .. } => "⇒",
             ASTNode::IfAndOnlyIf { .. } => "⟷",
+            ASTNode::Xor { .. } => "⊕",
+            ASTNode::Nand { .. } => "↑",
+            ASTNode::Nor { .. } => "↓",
+            ASTNode::Error { .. } => "?",
         }
     }
 
@@ -59,26 +232,29 @@ impl ASTNode {
     pub fn get_identifiers(&self) -> HashSet<&str> {
         let mut variables = HashSet::new();
         match self {
-            ASTNode::Identifier { name } => {
+            ASTNode::Identifier { name, .. } => {
                 variables.insert(name.as_str());
             },
-            ASTNode::Literal { .. } => {},
-            ASTNode::Not { operand } => {
+            ASTNode::Literal { .. } | ASTNode::Error { .. } => {},
+            ASTNode::Not { operand, .. } => {
                 variables.extend(operand.get_identifiers());
             },
-            ASTNode::And { left, right } => {
+            ASTNode::And { left, right, .. } => {
                 variables.extend(left.get_identifiers());
                 variables.extend(right.get_identifiers());
             },
-            ASTNode::Or { left, right } => {
+            ASTNode::Or { left, right, .. } => {
                 variables.extend(left.get_identifiers());
                 variables.extend(right.get_identifiers());
             },
-            ASTNode::Implies { left, right } => {
+            ASTNode::Implies { left, right, .. } => {
                 variables.extend(left.get_identifiers());
                 variables.extend(right.get_identifiers());
             },
-            ASTNode::IfAndOnlyIf { left, right } => {
+            ASTNode::IfAndOnlyIf { left, right, .. } |
+            ASTNode::Xor { left, right, .. } |
+            ASTNode::Nand { left, right, .. } |
+            ASTNode::Nor { left, right, .. } => {
                 variables.extend(left.get_identifiers());
                 variables.extend(right.get_identifiers());
             }
@@ -86,54 +262,128 @@ impl ASTNode {
         variables
     }
 
+    /// Returns the unique identifiers of the AST in order of first appearance,
+    /// from left to right.
+    pub fn get_ordered_identifiers(&self) -> Vec<&str> {
+        let mut variables: Vec<&str> = Vec::new();
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            match node {
+                ASTNode::Identifier { name, .. } => {
+                    if !variables.contains(&name.as_str()) {
+                        variables.push(name);
+                    }
+                },
+                ASTNode::Literal { .. } | ASTNode::Error { .. } => {},
+                ASTNode::Not { operand, .. } => stack.push(operand),
+                ASTNode::And { left, right, .. } |
+                ASTNode::Or { left, right, .. } |
+                ASTNode::Implies { left, right, .. } |
+                ASTNode::IfAndOnlyIf { left, right, .. } |
+                ASTNode::Xor { left, right, .. } |
+                ASTNode::Nand { left, right, .. } |
+                ASTNode::Nor { left, right, .. } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+            }
+        }
+        variables
+    }
+
+    /// Binding power used to decide where parentheses are needed when
+    /// formatting. It mirrors [`PrecedenceTable::textbook`](super::PrecedenceTable::textbook).
+    fn precedence(&self) -> u8 {
+        match self {
+            ASTNode::Identifier { .. } | ASTNode::Literal { .. } | ASTNode::Error { .. } => 6,
+            ASTNode::Not { .. } => 5,
+            ASTNode::And { .. } | ASTNode::Nand { .. } => 4,
+            ASTNode::Or { .. } | ASTNode::Xor { .. } | ASTNode::Nor { .. } => 3,
+            ASTNode::Implies { .. } => 2,
+            ASTNode::IfAndOnlyIf { .. } => 1,
+        }
+    }
+
     #[cfg(not(feature = "serde"))]
     pub fn as_json(&self) -> String {
+        let span = self.span()
+            .map(|s| format!(r#",
+                    "span": [{}, {}]"#, s.start, s.end))
+            .unwrap_or_default();
+
         match self {
-            ASTNode::Identifier { name } => {
+            ASTNode::Identifier { name, .. } => {
                 format!(r###"{{
                     "type": "identifier",
-                    "name": "{name}"
-                }}"###)
+                    "name": {name}{span}
+                }}"###, name = json_string(name))
             },
-            ASTNode::Literal { value } => {
+            ASTNode::Literal { value, .. } => {
                 format!(r###"{{
                     "type": "literal",
-                    "value": {value}
+                    "value": {value}{span}
                 }}"###)
             },
-            ASTNode::Not { operand } => {
+            ASTNode::Not { operand, .. } => {
                 format!(r###"{{
                     "type": "operator.not",
-                    "operand": {operand}
+                    "operand": {operand}{span}
                 }}"###, operand=operand.as_json())
             },
-            ASTNode::And { left, right } => {
+            ASTNode::And { left, right, .. } => {
                 format!(r###"{{
                     "type": "operator.and",
                     "left": {left},
-                    "right": {right}
+                    "right": {right}{span}
                 }}"###, left=left.as_json(), right=right.as_json())
             },
-            ASTNode::Or { left, right } => {
+            ASTNode::Or { left, right, .. } => {
                 format!(r###"{{
                     "type": "operator.or",
                     "left": {left},
-                    "right": {right}
+                    "right": {right}{span}
                 }}"###, left=left.as_json(), right=right.as_json())
             },
-            ASTNode::Implies { left, right } => {
+            ASTNode::Implies { left, right, .. } => {
                 format!(r###"{{
                     "type": "operator.implies",
                     "left": {left},
-                    "right": {right}
+                    "right": {right}{span}
                 }}"###, left=left.as_json(), right=right.as_json())
             },
-            ASTNode::IfAndOnlyIf { left, right } => {
+            ASTNode::IfAndOnlyIf { left, right, .. } => {
                 format!(r###"{{
                     "type": "operator.iff",
                     "left": {left},
-                    "right": {right}
+                    "right": {right}{span}
+                }}"###, left=left.as_json(), right=right.as_json())
+            },
+            ASTNode::Xor { left, right, .. } => {
+                format!(r###"{{
+                    "type": "operator.xor",
+                    "left": {left},
+                    "right": {right}{span}
+                }}"###, left=left.as_json(), right=right.as_json())
+            },
+            ASTNode::Nand { left, right, .. } => {
+                format!(r###"{{
+                    "type": "operator.nand",
+                    "left": {left},
+                    "right": {right}{span}
+                }}"###, left=left.as_json(), right=right.as_json())
+            },
+            ASTNode::Nor { left, right, .. } => {
+                format!(r###"{{
+                    "type": "operator.nor",
+                    "left": {left},
+                    "right": {right}{span}
                 }}"###, left=left.as_json(), right=right.as_json())
+            },
+            ASTNode::Error { .. } => {
+                format!(r###"{{
+                    "type": "error"{span}
+                }}"###)
             }
         }
     }
@@ -143,3 +393,74 @@ impl ASTNode {
         serde_json::to_string(self).unwrap()
     }
 }
+
+/// Formats the expression using the ASCII syntax accepted by the
+/// [`Lexer`](crate::lexing::Lexer), with the minimum amount of parentheses
+/// needed to be parsed back with the default [`Parser`](super::Parser).
+///
+/// `((p || q)) => (q && ~(r))` is formatted as `p | q -> q & ~r`. NAND and
+/// NOR are written with their keywords, `p nand q`. Identifiers that the
+/// default lexer would not read back as a single word are quoted, like
+/// `"tag:hot pink"`. [`ASTNode::Error`] nodes are written as `<error>`, which
+/// can't be parsed back.
+impl std::fmt::Display for ASTNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wrap = |f: &mut std::fmt::Formatter<'_>, node: &ASTNode, parens: bool| {
+            if parens { write!(f, "({node})") } else { write!(f, "{node}") }
+        };
+
+        match self {
+            ASTNode::Identifier { name, .. } => write_identifier(f, name),
+            ASTNode::Literal { value, .. } => write!(f, "{value}"),
+            ASTNode::Error { .. } => write!(f, "<error>"),
+            ASTNode::Not { operand, .. } => {
+                write!(f, "~")?;
+                wrap(f, operand, operand.precedence() < self.precedence())
+            },
+            ASTNode::And { left, right, .. } |
+            ASTNode::Or { left, right, .. } |
+            ASTNode::Implies { left, right, .. } |
+            ASTNode::IfAndOnlyIf { left, right, .. } |
+            ASTNode::Xor { left, right, .. } |
+            ASTNode::Nand { left, right, .. } |
+            ASTNode::Nor { left, right, .. } => {
+                let (op, right_associative) = match self {
+                    ASTNode::And { .. } => ("&", false),
+                    ASTNode::Or { .. } => ("|", false),
+                    ASTNode::Xor { .. } => ("^", false),
+                    ASTNode::Nand { .. } => ("nand", false),
+                    ASTNode::Nor { .. } => ("nor", false),
+                    ASTNode::Implies { .. } => ("->", true),
+                    _ => ("<->", true),
+                };
+                let p = self.precedence();
+                let (l, r) = (left.precedence(), right.precedence());
+
+                wrap(f, left, l < p || (l == p && right_associative))?;
+                write!(f, " {op} ")?;
+                wrap(f, right, r < p || (r == p && !right_associative))
+            }
+        }
+    }
+}
+
+/// Writes `name` so the default [`Lexer`](crate::lexing::Lexer) reads it
+/// back as a single identifier, quoting it if needed.
+pub(crate) fn write_identifier(f: &mut std::fmt::Formatter<'_>, name: &str) -> std::fmt::Result {
+    if is_plain_identifier(name) {
+        write!(f, "{name}")
+    } else {
+        write!(f, "\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Whether the default [`Lexer`](crate::lexing::Lexer) reads `name` as an
+/// identifier without quotes.
+fn is_plain_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let starts_word = chars.next().is_some_and(DEFAULT_START_ALPHABET);
+    starts_word
+        && chars.all(DEFAULT_ALPHABET)
+        && name != "true" && name != "false"
+        && !Keywords::is_standard(name)
+}
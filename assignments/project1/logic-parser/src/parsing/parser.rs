@@ -1,126 +1,354 @@
+use crate::diagnostics::SourceError;
 use crate::errors::ParserError;
-use crate::lexing::token::{Token, TokenKind};
+use crate::lexing::Lexer;
+use crate::lexing::token::{Token, TokenKind, Span};
 use ParserError::{UnexpectedToken, UnexpectedEOF};
 
 use super::node::ASTNode;
+use super::document::{Document, Statement};
+use super::expected::Expected;
+use super::precedence::{PrecedenceTable, Associativity};
 
 pub type Result<T> = std::result::Result<T, ParserError>;
 
 #[derive(Debug)]
 pub struct Parser<'a> {
     tokens: &'a Vec<Token>,
-    pos: usize
+    precedence: PrecedenceTable,
+    pos: usize,
+    /// Whether errors are collected instead of returned, see [`Parser::parse_recovering`]
+    recovering: bool,
+    errors: Vec<ParserError>,
+    /// Amount of open parentheses
+    depth: usize
+}
+
+/// Result of [`Parser::parse_recovering`] and [`parse_recovering`].
+#[derive(Debug)]
+pub struct Recovery {
+    /// Tree with [`ASTNode::Error`] nodes where the source couldn't be parsed
+    pub ast: ASTNode,
+    /// Errors in the order they appear in the source
+    pub errors: Vec<ParserError>
+}
+
+impl Recovery {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
 }
 
 impl Parser<'_> {
-    pub fn new(tokens: &Vec<Token>) -> Parser {
-        Parser { tokens, pos: 0 }
+    /// Creates a parser that uses the [`PrecedenceTable::textbook`] precedence.
+    pub fn new(tokens: &Vec<Token>) -> Parser<'_> {
+        Self::with_precedence(tokens, PrecedenceTable::default())
+    }
+
+    /// Creates a parser that resolves operators using a custom [`PrecedenceTable`].
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::{Parser, PrecedenceTable};
+    ///
+    /// let tokens = Lexer::new().tokenize("a & b | c").unwrap();
+    /// // parsed as a & (b | c), like in versions prior to 1.4.0
+    /// Parser::with_precedence(&tokens, PrecedenceTable::legacy()).parse().unwrap();
+    /// ```
+    pub fn with_precedence(tokens: &Vec<Token>, precedence: PrecedenceTable) -> Parser<'_> {
+        Parser { tokens, precedence, pos: 0, recovering: false, errors: vec![], depth: 0 }
     }
 
     /// Logic expressions parser
     ///
+    /// Binary operators are resolved by precedence climbing using the parser
+    /// [`PrecedenceTable`].
+    ///
     /// ```yaml
-    /// expr: term [(<-> | ->) expr]
-    /// term: prop [(|| | &&) term]
+    /// expr: prop [(<-> | -> | || | &&) expr]
     /// prop: [~] (true | false | "name" | LPAREN expr RPAREN)
     /// ```
     pub fn parse(&mut self) -> Result<ASTNode> {
         let ast = self.parse_expression()?;
         // If expression was not completedly parsed, return an error
-        if let Some(t) = self.consume() {
-            return Err(UnexpectedToken(format!("'{t}'", t=t.kind), t.span))
+        if let Some(t) = self.tokens.get(self.pos) {
+            let expected = self.expected_after_operand(false);
+            return Err(UnexpectedToken(format!("'{t}'", t=t.kind), t.span, expected))
         }
         Ok(ast)
     }
 
-    fn parse_expression(&mut self) -> Result<ASTNode> {
-        let l_term = self.parse_term()?;
-
-        match self.peek().cloned() {
-            Some(TokenKind::Implies) => {
-                self.consume();
-                Ok(ASTNode::Implies {
-                    left: Box::new(l_term),
-                    right: Box::new(self.parse_expression()?)
-                })
-            },
-            Some(TokenKind::IfAndOnlyIf) => {
-                self.consume();
-                Ok(ASTNode::IfAndOnlyIf {
-                    left: Box::new(l_term),
-                    right: Box::new(self.parse_expression()?)
-                })
-            },
-            Some(_) => Ok(l_term),
-            None => Ok(l_term)
-        }
+    /// Like [`Parser::parse`] but doesn't stop at the first error.
+    ///
+    /// Missing operands are replaced by [`ASTNode::Error`] nodes, and tokens
+    /// that can't follow an operand are skipped up to the next binary
+    /// operator or closing parenthesis. [`TokenKind::Error`] tokens, from
+    /// [`Lexer::tokenize_recovering`], become error nodes without reporting
+    /// them again.
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::Parser;
+    ///
+    /// let tokens = Lexer::new().tokenize("(p & ) | q r -> ~").unwrap();
+    /// let recovery = Parser::new(&tokens).parse_recovering();
+    ///
+    /// assert_eq!(recovery.ast.to_string(), "p & <error> | q -> ~<error>");
+    /// assert_eq!(recovery.errors.len(), 3);
+    /// ```
+    pub fn parse_recovering(&mut self) -> Recovery {
+        self.recovering = true;
+        let ast = self.parse_expression().expect("errors are collected when recovering");
+        Recovery { ast, errors: std::mem::take(&mut self.errors) }
     }
 
-    fn parse_term(&mut self) -> Result<ASTNode> {
-        let l_term = self.parse_proposition()?;
+    /// Parses many statements separated by [`TokenKind::Separator`], the
+    /// last one can be the conclusion after a [`TokenKind::Turnstile`]. Empty
+    /// statements are skipped, so newline separators can be used freely.
+    ///
+    /// ```
+    /// use logic_parser::lexing::{Lexer, LexerConfig, NewlineMode};
+    /// use logic_parser::parsing::Parser;
+    ///
+    /// let config = LexerConfig::standard().with_newlines(NewlineMode::Separator);
+    /// let mut lexer = Lexer::new().with_config(config).unwrap();
+    ///
+    /// let tokens = lexer.tokenize("p | q\n~p\n\n⊢ q").unwrap();
+    /// let document = Parser::new(&tokens).parse_document().unwrap();
+    /// assert_eq!(document.to_string(), "p | q; ~p |- q");
+    /// ```
+    pub fn parse_document(&mut self) -> Result<Document> {
+        let mut document = Document::default();
 
-        match self.peek().cloned() {
-            Some(TokenKind::And) => {
-                self.consume();
-                Ok(ASTNode::And {
-                    left: Box::new(l_term),
-                    right: Box::new(self.parse_term()?)
-                })
-            },
-            Some(TokenKind::Or) => {
-                self.consume();
-                Ok(ASTNode::Or {
-                    left: Box::new(l_term),
-                    right: Box::new(self.parse_term()?)
-                })
+        while let Some(kind) = self.peek() {
+            match kind {
+                TokenKind::Separator => {
+                    self.consume();
+                    continue;
+                },
+                TokenKind::Turnstile if document.conclusion.is_none() => {
+                    self.consume();
+                    document.conclusion = Some(self.parse_statement()?);
+                },
+                _ if document.conclusion.is_none() => document.premises.push(self.parse_statement()?),
+                _ => {}
+            }
+
+            // Statements end at a separator, the turnstile or the end of the input
+            let concluded = document.conclusion.is_some();
+            match self.tokens.get(self.pos) {
+                None => {},
+                Some(t) if t.kind == TokenKind::Separator => {},
+                Some(t) if t.kind == TokenKind::Turnstile && !concluded => {},
+                Some(t) => {
+                    let mut expected = self.expected_after_operand(false);
+                    let end = expected.pop();
+                    expected.push(Expected::Token(TokenKind::Separator));
+                    if !concluded {
+                        expected.push(Expected::Token(TokenKind::Turnstile));
+                    }
+                    expected.extend(end);
+                    return Err(UnexpectedToken(format!("'{}'", t.kind), t.span, expected))
+                }
+            }
+        }
+        Ok(document)
+    }
+
+    /// `[label :] expr`
+    fn parse_statement(&mut self) -> Result<Statement> {
+        let first = self.pos;
+        let label = match (self.peek().cloned(), self.tokens.get(self.pos + 1).map(|t| &t.kind)) {
+            (Some(TokenKind::Identifier(name)), Some(TokenKind::Colon)) => {
+                self.pos += 2;
+                Some(name)
             },
-            Some(_) => Ok(l_term),
-            None => Ok(l_term)
+            _ => None
+        };
+        let formula = self.parse_expression()?;
+        Ok(Statement { label, formula, span: self.span_since(first) })
+    }
+
+    fn parse_expression(&mut self) -> Result<ASTNode> {
+        self.parse_expression_bp(0)
+    }
+
+    /// Parses an expression whose operators bind at least as strong as `min_bp`.
+    fn parse_expression_bp(&mut self, min_bp: u16) -> Result<ASTNode> {
+        let first = self.pos;
+        let mut left = self.parse_proposition()?;
+
+        while let Some(kind) = self.peek().cloned() {
+            let op = match self.precedence.get_infix(&kind) {
+                Some(op) if op.precedence as u16 >= min_bp => op,
+                Some(_) => break,
+                None if self.recovering && !(kind == TokenKind::CloseParen && self.depth > 0) => {
+                    self.skip_unexpected();
+                    continue;
+                },
+                None => break
+            };
+            self.consume();
+
+            let next_bp = match op.associativity {
+                Associativity::Left => op.precedence as u16 + 1,
+                Associativity::Right => op.precedence as u16
+            };
+            let right = Box::new(self.parse_expression_bp(next_bp)?);
+            let l = Box::new(left);
+            let span = self.span_since(first);
+
+            left = match kind {
+                TokenKind::And => ASTNode::And { left: l, right, span },
+                TokenKind::Or => ASTNode::Or { left: l, right, span },
+                TokenKind::Implies => ASTNode::Implies { left: l, right, span },
+                TokenKind::IfAndOnlyIf => ASTNode::IfAndOnlyIf { left: l, right, span },
+                TokenKind::Xor => ASTNode::Xor { left: l, right, span },
+                TokenKind::Nand => ASTNode::Nand { left: l, right, span },
+                TokenKind::Nor => ASTNode::Nor { left: l, right, span },
+                _ => unreachable!("only binary operators have an infix precedence")
+            };
         }
+
+        Ok(left)
     }
 
     fn parse_proposition(&mut self) -> Result<ASTNode> {
+        let first = self.pos;
         let next_token = match self.consume().cloned() {
             Some(t) => t,
             None => {
                 // Gets the last token span, otherwise (start: 0, end: 0)
                 let last_span = self.tokens.last().map(|t| t.span).unwrap_or((0, 0).into());
-                return Err(
-                    UnexpectedEOF("Expected [~] (true | false | variable | (...))".into(), last_span)
+                return self.recover(
+                    UnexpectedEOF("Expected [~] (true | false | variable | (...))".into(), last_span, Expected::operand()),
+                    (last_span.end, last_span.end).into()
                 )
             },
         };
 
         match next_token.kind {
             TokenKind::Identifier(name) => {
-                Ok(ASTNode::Identifier { name: name.to_owned() })
+                Ok(ASTNode::Identifier { name: name.to_owned(), span: self.span_since(first) })
             },
             TokenKind::Literal(boolean) => {
-                Ok(ASTNode::Literal { value: boolean })
+                Ok(ASTNode::Literal { value: boolean, span: self.span_since(first) })
             },
             TokenKind::Not => {
-                let prop = self.parse_proposition()?;
-                Ok(ASTNode::Not{ operand: Box::new(prop) })
+                let bp = self.precedence.get(&TokenKind::Not).map(|p| p.precedence as u16).unwrap_or(u16::MAX);
+                let prop = self.parse_expression_bp(bp)?;
+                Ok(ASTNode::Not{ operand: Box::new(prop), span: self.span_since(first) })
             },
             TokenKind::OpenParen => {
-                let expr = self.parse_expression()?;
+                self.depth += 1;
+                let expr = self.parse_expression();
+                self.depth -= 1;
+                let expr = expr?;
+
                 if let Some(TokenKind::CloseParen) = self.peek() {
                     self.consume();
-                    Ok(expr)
+                    // The parenthesised expression spans its parentheses too
+                    Ok(expr.with_span(self.span_since(first)))
                 }
                 else {
-                    Err(UnexpectedToken("R_PAREN expected".into(), next_token.span))
+                    // Reported where the parenthesis should be inserted
+                    let expected = self.expected_after_operand(true);
+                    let error = match self.tokens.get(self.pos) {
+                        Some(t) => UnexpectedToken("R_PAREN expected".into(), t.span, expected),
+                        None => UnexpectedEOF("R_PAREN expected".into(), self.end_of_input(), expected)
+                    };
+                    if !self.recovering {
+                        return Err(error);
+                    }
+                    // The expression is kept, only the parenthesis is missing
+                    self.errors.push(error);
+                    Ok(expr.with_span(self.span_since(first)))
                 }
             },
+            TokenKind::CloseParen if self.recovering && self.depth == 0 => {
+                // Unmatched, it's skipped and the operand is searched after it
+                self.errors.push(UnexpectedToken("R_PAREN".into(), next_token.span, Expected::operand()));
+                self.parse_proposition()
+            },
             TokenKind::CloseParen => {
-                Err(UnexpectedToken("R_PAREN".into(), next_token.span))
+                self.pos = first;
+                self.recover(
+                    UnexpectedToken("R_PAREN".into(), next_token.span, Expected::operand()),
+                    (next_token.span.start, next_token.span.start).into()
+                )
             },
-            other @ (TokenKind::And | TokenKind::Or | TokenKind::Implies | TokenKind::IfAndOnlyIf) => {
-                Err(UnexpectedToken(format!("'{other}'"), next_token.span))
+            TokenKind::Error if self.recovering => {
+                // Already reported by the lexer
+                Ok(ASTNode::Error { span: Some(next_token.span) })
+            },
+            other @ (TokenKind::And | TokenKind::Or | TokenKind::Implies | TokenKind::IfAndOnlyIf |
+                     TokenKind::Xor | TokenKind::Nand | TokenKind::Nor | TokenKind::Separator |
+                     TokenKind::Colon | TokenKind::Turnstile | TokenKind::Error) => {
+                // The operand is missing, the token is left for the caller
+                self.pos = first;
+                self.recover(
+                    UnexpectedToken(format!("'{other}'"), next_token.span, Expected::operand()),
+                    (next_token.span.start, next_token.span.start).into()
+                )
+            }
+        }
+    }
+
+    /// In recovering mode `error` is collected and an [`ASTNode::Error`] at
+    /// `span` takes the place of the operand, otherwise it's returned.
+    fn recover(&mut self, error: ParserError, span: Span) -> Result<ASTNode> {
+        if !self.recovering {
+            return Err(error);
+        }
+        self.errors.push(error);
+        Ok(ASTNode::Error { span: Some(span) })
+    }
+
+    /// Skips the tokens that can't follow an operand, up to the next binary
+    /// operator or closing parenthesis, and reports them as a single error.
+    fn skip_unexpected(&mut self) {
+        let first = self.pos;
+        let mut nested = 0;
+
+        while let Some(kind) = self.peek() {
+            match kind {
+                TokenKind::OpenParen => nested += 1,
+                TokenKind::CloseParen if nested > 0 => nested -= 1,
+                TokenKind::CloseParen if self.depth > 0 => break,
+                _ if nested == 0 && self.pos > first && self.precedence.get_infix(kind).is_some() => break,
+                _ => {}
             }
+            self.pos += 1;
+        }
+
+        // Error tokens were already reported by the lexer
+        let skipped = &self.tokens[first..self.pos];
+        if let Some(unexpected) = skipped.iter().find(|t| t.kind != TokenKind::Error) {
+            let end = skipped.last().map(|t| t.span.end).unwrap_or(unexpected.span.end);
+            let expected = self.expected_after_operand(self.depth > 0);
+            self.errors.push(UnexpectedToken(format!("'{}'", unexpected.kind), (unexpected.span.start, end).into(), expected));
         }
     }
 
+    /// Tokens that can follow a complete operand: a binary operator, and
+    /// the closing parenthesis or the end of the input.
+    fn expected_after_operand(&self, in_parens: bool) -> Vec<Expected> {
+        let mut expected: Vec<Expected> = self.precedence.infix_operators().cloned().map(Expected::Token).collect();
+        expected.push(if in_parens { Expected::Token(TokenKind::CloseParen) } else { Expected::EndOfInput });
+        expected
+    }
+
+    /// Empty span right after the last token.
+    fn end_of_input(&self) -> Span {
+        let end = self.tokens.last().map(|t| t.span.end).unwrap_or(0);
+        (end, end).into()
+    }
+
+    /// Span from the token at index `first` to the last consumed token.
+    fn span_since(&self, first: usize) -> Option<Span> {
+        let (start, end) = (self.tokens.get(first)?, self.tokens.get(self.pos.checked_sub(1)?)?);
+        Some(Span { start: start.span.start, end: end.span.end })
+    }
+
     fn consume(&mut self) -> Option<&Token> {
         let token = self.tokens.get(self.pos);
         if token.is_some() {
@@ -134,6 +362,34 @@ impl Parser<'_> {
     }
 }
 
+/// Tokenizes and parses `src` in a single pass collecting every lexer and
+/// parser error, see [`Lexer::tokenize_recovering`] and
+/// [`Parser::parse_recovering`]. Lexer errors are wrapped in
+/// [`ParserError::LexingError`].
+///
+/// ```
+/// use logic_parser::lexing::Lexer;
+/// use logic_parser::parsing::parse_recovering;
+///
+/// let recovery = parse_recovering(&mut Lexer::new(), "p & $ | (q -> ");
+/// assert_eq!(recovery.ast.to_string(), "p & <error> | (q -> <error>)");
+///
+/// let messages: Vec<String> = recovery.errors.iter().map(|e| e.to_string()).collect();
+/// assert_eq!(messages, vec![
+///     "Lexing error: Unknown Token: $",
+///     "Unexpected EOF: Expected [~] (true | false | variable | (...))",
+///     "Unexpected EOF: R_PAREN expected",
+/// ]);
+/// ```
+pub fn parse_recovering<'a>(lexer: &mut Lexer<'a>, src: &'a str) -> Recovery {
+    let (tokens, lexer_errors) = lexer.tokenize_recovering(src);
+    let mut recovery = Parser::new(&tokens).parse_recovering();
+
+    recovery.errors.extend(lexer_errors.into_iter().map(ParserError::from));
+    recovery.errors.sort_by_key(|e| e.span().start);
+    recovery
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -179,6 +435,20 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn quoted_identifiers_are_escaped_in_json() -> Result<(), Box<dyn Error>> {
+        use assert_json::assert_json;
+        let tokens = Lexer::new().tokenize(r#""R&D <x>" & 'a"b\\c'"#)?;
+        let ast = Parser::new(&tokens).parse()?;
+
+        assert_json!(ast.as_json().as_str(), {
+            "type": "operator.and",
+            "left": { "type": "identifier", "name": "R&D <x>" },
+            "right": { "type": "identifier", "name": "a\"b\\c" }
+        });
+        Ok(())
+    }
+
     #[test]
     fn multiple_negation_works() -> Result<(), Box<dyn Error>> {
         use assert_json::assert_json;
@@ -277,9 +547,11 @@ mod test {
         let tokens = Lexer::new().tokenize("((a => b) <=> c").unwrap();
         let parse_error = Parser::new(&tokens).parse().unwrap_err();
 
+        // Reported where the parenthesis is missing, not where it was opened
         match parse_error {
-            ParserError::UnexpectedToken(_, span) => {
-                assert_eq!(span, (0, 1).into())
+            ParserError::UnexpectedEOF(_, span, expected) => {
+                assert_eq!(span, (15, 15).into());
+                assert_eq!(expected.last(), Some(&Expected::Token(TokenKind::CloseParen)));
             },
             _ => unreachable!()
         }
@@ -291,13 +563,105 @@ mod test {
 
         let parse_error = Parser::new(&tokens).parse().unwrap_err();
         match parse_error {
-            ParserError::UnexpectedToken(_, span) => {
+            ParserError::UnexpectedToken(_, span, _) => {
                 assert_eq!(span, (8, 9).into())
             },
             _ => unreachable!()
         }
     }
 
+    #[test]
+    fn errors_report_the_expected_tokens() {
+        let operators = |last: Expected| {
+            let mut expected: Vec<Expected> = [
+                TokenKind::And, TokenKind::Nand, TokenKind::Or, TokenKind::Xor,
+                TokenKind::Nor, TokenKind::Implies, TokenKind::IfAndOnlyIf
+            ].into_iter().map(Expected::Token).collect();
+            expected.push(last);
+            expected
+        };
+        let cases = [
+            ("p ->", Expected::operand()),
+            ("p & )", Expected::operand()),
+            ("p q", operators(Expected::EndOfInput)),
+            ("(p q)", operators(Expected::Token(TokenKind::CloseParen))),
+        ];
+
+        for (expression, expected) in cases {
+            let tokens = Lexer::new().tokenize(expression).unwrap();
+            let error = Parser::new(&tokens).parse().unwrap_err();
+            assert_eq!(error.expected(), expected, "{expression}");
+        }
+
+        let tokens = Lexer::new().tokenize("~(a b c").unwrap();
+        let errors = Parser::new(&tokens).parse_recovering().errors;
+        assert_eq!(errors.iter().map(|e| e.expected().last()).collect::<Vec<_>>(), vec![
+            Some(&Expected::Token(TokenKind::CloseParen)),
+            Some(&Expected::Token(TokenKind::CloseParen))
+        ]);
+    }
+
+    #[test]
+    fn documents_are_parsed() -> Result<(), Box<dyn Error>> {
+        let source = "P1: p -> q;; q -> r ; 'p 3': p ⊢ r;";
+        let tokens = Lexer::new().tokenize(source)?;
+        let document = Parser::new(&tokens).parse_document()?;
+
+        let labels: Vec<Option<&str>> = document.premises.iter().map(|s| s.label.as_deref()).collect();
+        assert_eq!(labels, vec![Some("P1"), None, Some("p 3")]);
+        assert_eq!(document.premises[0].span, Some((0, 10).into()));
+        assert_eq!(document.premises[2].span, Some((22, 30).into()));
+        let formatted = document.to_string();
+        assert_eq!(formatted, "P1: p -> q; q -> r; \"p 3\": p |- r");
+
+        let tokens = Lexer::new().tokenize(&formatted)?;
+        assert_eq!(Parser::new(&tokens).parse_document()?, document);
+
+        let tokens = Lexer::new().tokenize("p -> q")?;
+        let document = Parser::new(&tokens).parse_document()?;
+        assert_eq!((document.premises.len(), document.conclusion), (1, None));
+        Ok(())
+    }
+
+    #[test]
+    fn documents_are_rendered_in_json() -> Result<(), Box<dyn Error>> {
+        use assert_json::assert_json;
+        let tokens = Lexer::new().tokenize("P1: a; b |- a")?;
+        let document = Parser::new(&tokens).parse_document()?;
+
+        assert_json!(document.as_json().as_str(), {
+            "premises": [
+                { "label": "P1", "formula": { "type": "identifier", "name": "a" }, "span": [0, 5] },
+                { "formula": { "type": "identifier", "name": "b" }, "span": [7, 8] }
+            ],
+            "conclusion": { "formula": { "type": "identifier", "name": "a", "span": [12, 13] } }
+        });
+        assert!(!document.premises[1].as_json().contains("label"));
+
+        let tokens = Lexer::new().tokenize(r#"'say "hi"': a"#)?;
+        let document = Parser::new(&tokens).parse_document()?;
+        assert_json!(document.as_json().as_str(), {
+            "premises": [{ "label": "say \"hi\"", "formula": { "type": "identifier", "name": "a" } }]
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_documents_result_on_error() {
+        let cases = [
+            ("p q; r", (2, 3), Expected::Token(TokenKind::Turnstile)),
+            ("p |- q |- r", (7, 9), Expected::Token(TokenKind::Separator)),
+            ("p; |- q r", (8, 9), Expected::Token(TokenKind::Separator)),
+            ("P1: |- q", (4, 6), Expected::Identifier),
+        ];
+        for (source, span, expected) in cases {
+            let tokens = Lexer::new().tokenize(source).unwrap();
+            let error = Parser::new(&tokens).parse_document().unwrap_err();
+            assert_eq!(error.span(), span.into(), "{source}");
+            assert!(error.expected().contains(&expected), "{source}");
+        }
+    }
+
     #[test]
     fn parsing_custom_expressions() {
         let query = "(tag:pink || tag:anime) && (mime:image/* || mime:video/*)";
@@ -311,4 +675,252 @@ mod test {
         let mut parser = Parser::new(&tokens);
         parser.parse().unwrap();
     }
+
+    #[test]
+    fn textbook_precedence_is_the_default() -> Result<(), Box<dyn Error>> {
+        use assert_json::assert_json;
+        let tokens = Lexer::new().tokenize("a | b & c -> ~d")?;
+        let ast = Parser::new(&tokens).parse()?;
+        let result = ast.as_json();
+
+        assert_json!(result.as_str(), {
+            "type": "operator.implies",
+            "left": {
+                "type": "operator.or",
+                "left": {
+                    "type": "identifier",
+                    "name": "a"
+                },
+                "right": {
+                    "type": "operator.and",
+                    "left": {
+                        "type": "identifier",
+                        "name": "b"
+                    },
+                    "right": {
+                        "type": "identifier",
+                        "name": "c"
+                    }
+                }
+            },
+            "right": {
+                "type": "operator.not",
+                "operand": {
+                    "type": "identifier",
+                    "name": "d"
+                }
+            }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn associativity_is_respected() -> Result<(), Box<dyn Error>> {
+        use assert_json::assert_json;
+        let tokens = Lexer::new().tokenize("a & b & c")?;
+        let ast = Parser::new(&tokens).parse()?;
+
+        assert_json!(ast.as_json().as_str(), {
+            "type": "operator.and",
+            "left": {
+                "type": "operator.and",
+                "left": { "type": "identifier", "name": "a" },
+                "right": { "type": "identifier", "name": "b" }
+            },
+            "right": { "type": "identifier", "name": "c" }
+        });
+
+        let tokens = Lexer::new().tokenize("a -> b -> c")?;
+        let ast = Parser::new(&tokens).parse()?;
+
+        assert_json!(ast.as_json().as_str(), {
+            "type": "operator.implies",
+            "left": { "type": "identifier", "name": "a" },
+            "right": {
+                "type": "operator.implies",
+                "left": { "type": "identifier", "name": "b" },
+                "right": { "type": "identifier", "name": "c" }
+            }
+        });
+
+        // Not associative, but grouped from the left like `&` and `|`
+        let tokens = Lexer::new().tokenize("a nand b nand c")?;
+        let ast = Parser::new(&tokens).parse()?;
+
+        assert_json!(ast.as_json().as_str(), {
+            "type": "operator.nand",
+            "left": {
+                "type": "operator.nand",
+                "left": { "type": "identifier", "name": "a" },
+                "right": { "type": "identifier", "name": "b" }
+            },
+            "right": { "type": "identifier", "name": "c" }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_precedence_groups_to_the_right() -> Result<(), Box<dyn Error>> {
+        use assert_json::assert_json;
+        let tokens = Lexer::new().tokenize("a & b | c")?;
+        let ast = Parser::with_precedence(&tokens, PrecedenceTable::legacy()).parse()?;
+
+        assert_json!(ast.as_json().as_str(), {
+            "type": "operator.and",
+            "left": { "type": "identifier", "name": "a" },
+            "right": {
+                "type": "operator.or",
+                "left": { "type": "identifier", "name": "b" },
+                "right": { "type": "identifier", "name": "c" }
+            }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn negation_precedence_can_be_lowered() -> Result<(), Box<dyn Error>> {
+        use assert_json::assert_json;
+        let table = PrecedenceTable::textbook().with(TokenKind::Not, 1, Associativity::Right);
+        let tokens = Lexer::new().tokenize("~a & b")?;
+        let ast = Parser::with_precedence(&tokens, table).parse()?;
+
+        assert_json!(ast.as_json().as_str(), {
+            "type": "operator.not",
+            "operand": {
+                "type": "operator.and",
+                "left": { "type": "identifier", "name": "a" },
+                "right": { "type": "identifier", "name": "b" }
+            }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn formatted_expressions_can_be_parsed_back() -> Result<(), Box<dyn Error>> {
+        let cases = [
+            ("((p || q)) => (q && ~(r))", "p | q -> q & ~r"),
+            ("(a -> b) -> c", "(a -> b) -> c"),
+            ("a & (b & c)", "a & (b & c)"),
+            ("~(a <-> ~b)", "~(a <-> ~b)"),
+            ("(true | x) & false", "(true | x) & false"),
+            ("a ⊕ b ⊕ c", "a ^ b ^ c"),
+            ("a ↑ (b ↓ c)", "a nand (b nor c)"),
+            ("(a nand b) & c", "a nand b & c"),
+            ("(a ↓ b) ↓ c", "a nor b nor c"),
+            ("a nor (b nor c)", "a nor (b nor c)"),
+            ("a | (b xor c)", "a | (b ^ c)"),
+            ("'tag:hot pink' & \"say \\\"hi\\\"\" & 'or'", "\"tag:hot pink\" & \"say \\\"hi\\\"\" & \"or\""),
+        ];
+
+        for (expression, expected) in cases {
+            let tokens = Lexer::new().tokenize(expression)?;
+            let formatted = Parser::new(&tokens).parse()?.to_string();
+            assert_eq!(formatted, expected);
+
+            let tokens = Lexer::new().tokenize(&formatted)?;
+            assert_eq!(Parser::new(&tokens).parse()?.to_string(), expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn nodes_carry_their_spans() -> Result<(), Box<dyn Error>> {
+        let tokens = Lexer::new().tokenize("~(p & q) -> r")?;
+        let ast = Parser::new(&tokens).parse()?;
+        let span = |path: &[usize]| ast.subterm(path).and_then(ASTNode::span).map(|s| (s.start, s.end));
+
+        assert_eq!(span(&[]), Some((0, 13)));
+        assert_eq!(span(&[0]), Some((0, 8)));
+        // Parenthesised expressions include their parentheses
+        assert_eq!(span(&[0, 0]), Some((1, 8)));
+        assert_eq!(span(&[0, 0, 1]), Some((6, 7)));
+        assert_eq!(span(&[1]), Some((12, 13)));
+        Ok(())
+    }
+
+    #[test]
+    fn spans_are_rendered_in_json() -> Result<(), Box<dyn Error>> {
+        use assert_json::assert_json;
+        let tokens = Lexer::new().tokenize("(a) | b")?;
+        let ast = Parser::new(&tokens).parse()?;
+
+        assert_json!(ast.as_json().as_str(), {
+            "type": "operator.or",
+            "left": { "type": "identifier", "name": "a", "span": [0, 3] },
+            "right": { "type": "identifier", "name": "b", "span": [6, 7] },
+            "span": [0, 7]
+        });
+
+        let unparsed = ASTNode::Identifier { name: "a".into(), span: None };
+        assert!(!unparsed.as_json().contains("span"));
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn spans_survive_serde_round_trips() -> Result<(), Box<dyn Error>> {
+        let tokens = Lexer::new().tokenize("a -> (b & ~c)")?;
+        let ast = Parser::new(&tokens).parse()?;
+        let deserialized: ASTNode = serde_json::from_str(&ast.as_json())?;
+
+        assert_eq!(deserialized, ast);
+        for path in [&[][..], &[1], &[1, 1], &[1, 1, 0]] {
+            assert_eq!(deserialized.subterm(path).and_then(ASTNode::span), ast.subterm(path).and_then(ASTNode::span));
+        }
+
+        // Nodes without span can still be read
+        let node: ASTNode = serde_json::from_str(r#"{ "type": "identifier", "name": "a" }"#)?;
+        assert_eq!(node.span(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn recovering_parser_collects_every_error() {
+        let cases = [
+            ("p & & q", "p & <error> & q", 1),
+            ("(p | q", "p | q", 1),
+            ("p q r & s", "p & s", 1),
+            (") p -> (q <->)", "p -> (q <-> <error>)", 2),
+            ("~", "~<error>", 1),
+            ("", "<error>", 1),
+            ("a & (b c) | d)", "a & b | d", 2),
+        ];
+
+        for (expression, expected, errors) in cases {
+            let tokens = Lexer::new().tokenize(expression).unwrap();
+            let recovery = Parser::new(&tokens).parse_recovering();
+            assert_eq!(recovery.ast.to_string(), expected, "{expression}");
+            assert_eq!(recovery.errors.len(), errors, "{expression}");
+        }
+    }
+
+    #[test]
+    fn recovering_parser_agrees_on_valid_input() -> Result<(), Box<dyn Error>> {
+        let tokens = Lexer::new().tokenize("(p -> q) & ~(r | s)")?;
+        let recovery = Parser::new(&tokens).parse_recovering();
+
+        assert!(recovery.is_ok());
+        assert!(!recovery.ast.has_errors());
+        assert_eq!(recovery.ast, Parser::new(&tokens).parse()?);
+        Ok(())
+    }
+
+    #[test]
+    fn error_nodes_point_at_the_missing_operand() {
+        let recovery = parse_recovering(&mut Lexer::new(), "p -> $ & (q |)");
+        let span = |path: &[usize]| recovery.ast.subterm(path).and_then(ASTNode::span).map(|s| (s.start, s.end));
+
+        assert!(matches!(recovery.ast.subterm(&[1, 0]), Some(ASTNode::Error { .. })));
+        assert_eq!(span(&[1, 0]), Some((5, 6)));
+        assert_eq!(span(&[1, 1, 1]), Some((13, 13)));
+
+        let spans: Vec<Span> = recovery.errors.iter().map(|e| e.span()).collect();
+        assert_eq!(spans, vec![(5, 6).into(), (13, 14).into()]);
+        assert!(matches!(recovery.errors[0], ParserError::LexingError(_)));
+
+        match recovery.ast.evaluate(&[("p", true), ("q", true)]).unwrap_err() {
+            crate::errors::EvaluationError::ErrorNode(span) => assert_eq!(span, Some((5, 6).into())),
+            _ => unreachable!()
+        }
+    }
 }
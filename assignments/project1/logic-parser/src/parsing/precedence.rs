@@ -0,0 +1,120 @@
+use crate::lexing::token::TokenKind;
+
+/// How operators of the same precedence group together.
+///
+/// `a -> b -> c` is parsed as `a -> (b -> c)` when [`Associativity::Right`] and
+/// as `(a -> b) -> c` when [`Associativity::Left`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Associativity {
+    Left,
+    Right
+}
+
+/// The binding power of a single operator. Higher precedences bind tighter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperatorPrecedence {
+    pub precedence: u8,
+    pub associativity: Associativity
+}
+
+/// Table of binding powers used by the [`Parser`](super::Parser) to resolve
+/// expressions without parentheses.
+///
+/// [`TokenKind::Not`] is always parsed as a prefix operator, its precedence
+/// decides which binary operators are captured by its operand. Every other
+/// operator in the table is parsed as an infix operator.
+///
+/// ```
+/// use logic_parser::lexing::{Lexer, token::TokenKind};
+/// use logic_parser::parsing::{Parser, PrecedenceTable, Associativity};
+///
+/// // make '&&' and '||' equally strong
+/// let table = PrecedenceTable::textbook()
+///     .with(TokenKind::Or, 4, Associativity::Left);
+///
+/// let tokens = Lexer::new().tokenize("a || b && c").unwrap();
+/// Parser::with_precedence(&tokens, table).parse().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrecedenceTable {
+    operators: Vec<(TokenKind, OperatorPrecedence)>
+}
+
+impl PrecedenceTable {
+    /// Creates a table with no operators at all.
+    pub fn empty() -> Self {
+        PrecedenceTable { operators: vec![] }
+    }
+
+    /// The usual precedence found in logic textbooks: `¬ > ∧ > ∨ > → > ↔`.
+    ///
+    /// `∧` and `∨` are left associative, `→` and `↔` are right associative.
+    /// `↑` groups like `∧`, while `⊕` and `↓` group like `∨`. `↑` and `↓`
+    /// aren't associative, but chains are still read from the left:
+    /// `a ↑ b ↑ c` is `(a ↑ b) ↑ c`.
+    pub fn textbook() -> Self {
+        Self::empty()
+            .with(TokenKind::Not, 5, Associativity::Right)
+            .with(TokenKind::And, 4, Associativity::Left)
+            .with(TokenKind::Nand, 4, Associativity::Left)
+            .with(TokenKind::Or, 3, Associativity::Left)
+            .with(TokenKind::Xor, 3, Associativity::Left)
+            .with(TokenKind::Nor, 3, Associativity::Left)
+            .with(TokenKind::Implies, 2, Associativity::Right)
+            .with(TokenKind::IfAndOnlyIf, 1, Associativity::Right)
+    }
+
+    /// The behaviour of the parser before the precedence table existed.
+    ///
+    /// `&&` and `||` share the same level and `->` and `<->` share a lower
+    /// one. Everything is right associative, so `a & b | c` is parsed as
+    /// `a & (b | c)`.
+    pub fn legacy() -> Self {
+        Self::empty()
+            .with(TokenKind::Not, 3, Associativity::Right)
+            .with(TokenKind::And, 2, Associativity::Right)
+            .with(TokenKind::Or, 2, Associativity::Right)
+            .with(TokenKind::Xor, 2, Associativity::Right)
+            .with(TokenKind::Nand, 2, Associativity::Right)
+            .with(TokenKind::Nor, 2, Associativity::Right)
+            .with(TokenKind::Implies, 1, Associativity::Right)
+            .with(TokenKind::IfAndOnlyIf, 1, Associativity::Right)
+    }
+
+    /// Sets (or replaces) the binding power of an operator.
+    ///
+    /// A precedence of `0` is reserved and will be bumped to `1`.
+    pub fn with(mut self, kind: TokenKind, precedence: u8, associativity: Associativity) -> Self {
+        let entry = OperatorPrecedence { precedence: precedence.max(1), associativity };
+        match self.operators.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, old)) => *old = entry,
+            None => self.operators.push((kind, entry))
+        }
+        self
+    }
+
+    /// Returns the binding power of the given operator, if it is in the table.
+    pub fn get(&self, kind: &TokenKind) -> Option<OperatorPrecedence> {
+        self.operators.iter().find(|(k, _)| k == kind).map(|(_, p)| *p)
+    }
+
+    /// Like [`PrecedenceTable::get`] but only for operators that can appear
+    /// between two operands.
+    pub(crate) fn get_infix(&self, kind: &TokenKind) -> Option<OperatorPrecedence> {
+        match kind {
+            TokenKind::Not => None,
+            other => self.get(other)
+        }
+    }
+
+    /// The operators that can appear between two operands, in table order.
+    pub(crate) fn infix_operators(&self) -> impl Iterator<Item = &TokenKind> {
+        self.operators.iter().map(|(k, _)| k).filter(|k| **k != TokenKind::Not)
+    }
+}
+
+impl Default for PrecedenceTable {
+    fn default() -> Self {
+        Self::textbook()
+    }
+}
@@ -0,0 +1,19 @@
+use crate::lexing::Lexer;
+use super::{ASTNode, Document, Parser, parse_recovering};
+
+/// Parses an expression that is known to be valid.
+pub(crate) fn parse(expression: &str) -> ASTNode {
+    let tokens = Lexer::new().tokenize(expression).unwrap();
+    Parser::new(&tokens).parse().unwrap()
+}
+
+/// Parses an expression with errors, keeping the partial tree.
+pub(crate) fn parse_recovered(expression: &str) -> ASTNode {
+    parse_recovering(&mut Lexer::new(), expression).ast
+}
+
+/// Parses a document that is known to be valid.
+pub(crate) fn parse_document(document: &str) -> Document {
+    let tokens = Lexer::new().tokenize(document).unwrap();
+    Parser::new(&tokens).parse_document().unwrap()
+}
@@ -0,0 +1,65 @@
+use crate::evaluating::Model;
+use crate::transforming::Cnf;
+use super::solver::Solver;
+
+impl Cnf {
+    /// Identifiers of the clauses in order of first appearance. The position
+    /// of each identifier plus one is its variable number for the [`Solver`].
+    pub fn identifiers(&self) -> Vec<&str> {
+        let mut identifiers: Vec<&str> = Vec::new();
+        for literal in self.clauses.iter().flatten() {
+            if !identifiers.contains(&literal.name.as_str()) {
+                identifiers.push(&literal.name);
+            }
+        }
+        identifiers
+    }
+
+    /// Clauses in DIMACS notation, numbering identifiers as
+    /// [`Cnf::identifiers`] does.
+    pub fn numbered_clauses(&self) -> Vec<Vec<i64>> {
+        let identifiers = self.identifiers();
+        self.clauses.iter().map(|clause| {
+            clause.iter().map(|literal| {
+                let v = identifiers.iter().position(|i| *i == literal.name)
+                    .expect("every literal has an identifier") as i64 + 1;
+                if literal.negated { -v } else { v }
+            }).collect()
+        }).collect()
+    }
+
+    /// Finds an assignment that satisfies every clause using the CDCL
+    /// [`Solver`], or `None` if the clauses are unsatisfiable.
+    pub fn solve(&self) -> Option<Model> {
+        let identifiers = self.identifiers();
+        let mut solver = Solver::new();
+        solver.reserve_vars(identifiers.len());
+        for clause in self.numbered_clauses() {
+            solver.add_clause(&clause);
+        }
+
+        let values = solver.solve()?;
+        Some(identifiers.into_iter().map(String::from).zip(values).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsing::testing::parse;
+    use crate::transforming::Cnf;
+
+    #[test]
+    fn clauses_are_numbered_by_appearance() {
+        let cnf = Cnf::from_ast(&parse("(b | ~a) & (a | c)")).unwrap();
+        assert_eq!(cnf.identifiers(), vec!["b", "a", "c"]);
+        assert_eq!(cnf.numbered_clauses(), vec![vec![1, -2], vec![2, 3]]);
+    }
+
+    #[test]
+    fn models_satisfy_the_expression() {
+        let ast = parse("(p -> q) & (q -> r) & p & (s | ~r)");
+        let model = Cnf::from_ast(&ast).unwrap().solve().unwrap();
+        assert!(ast.evaluate(&model).unwrap());
+        assert!(Cnf::from_ast(&parse("(p -> q) & p & ~q")).unwrap().solve().is_none());
+    }
+}
@@ -0,0 +1,4 @@
+mod solver;
+mod cnf;
+
+pub use solver::*;
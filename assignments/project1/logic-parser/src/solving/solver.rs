@@ -0,0 +1,450 @@
+/// Encoded literal: `2 * var` for the positive literal and `2 * var + 1` for
+/// the negated one.
+type Lit = usize;
+
+const fn var(lit: Lit) -> usize { lit >> 1 }
+const fn neg(lit: Lit) -> Lit { lit ^ 1 }
+
+/// CDCL SAT solver.
+///
+/// Clauses use the DIMACS convention: variables are numbered from `1` and a
+/// negative number is the negation of the variable. The solver implements unit
+/// propagation with two watched literals, first-UIP clause learning with
+/// non-chronological backjumping, VSIDS-like branching with phase saving and
+/// Luby restarts.
+///
+/// ```
+/// use logic_parser::solving::Solver;
+///
+/// let mut solver = Solver::new();
+/// solver.add_clause(&[1, 2]);
+/// solver.add_clause(&[-1, 2]);
+/// solver.add_clause(&[-2, 3]);
+///
+/// let model = solver.solve().unwrap();
+/// assert!(model[1] && model[2]); // x2 and x3 must be true
+///
+/// solver.add_clause(&[-3]);
+/// assert!(solver.solve().is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    /// Clauses watching each literal, visited when the literal becomes false
+    watches: Vec<Vec<usize>>,
+    assigns: Vec<Option<bool>>,
+    level: Vec<usize>,
+    reason: Vec<Option<usize>>,
+    activity: Vec<f64>,
+    phase: Vec<bool>,
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    qhead: usize,
+    var_inc: f64,
+    /// Set when an empty clause (or two contradicting units) is added
+    inconsistent: bool
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Solver { var_inc: 1.0, ..Default::default() }
+    }
+
+    /// Number of variables known by the solver.
+    pub fn num_vars(&self) -> usize {
+        self.assigns.len()
+    }
+
+    /// Makes sure variables `1..=n` exist, even if no clause mentions them.
+    pub fn reserve_vars(&mut self, n: usize) {
+        while self.assigns.len() < n {
+            self.assigns.push(None);
+            self.level.push(0);
+            self.reason.push(None);
+            self.activity.push(0.0);
+            self.phase.push(false);
+            self.watches.push(vec![]);
+            self.watches.push(vec![]);
+        }
+    }
+
+    /// Adds a clause in DIMACS notation. `0` is not a valid literal and is
+    /// ignored.
+    pub fn add_clause(&mut self, clause: &[i64]) {
+        self.cancel_until(0);
+
+        let mut lits: Vec<Lit> = Vec::with_capacity(clause.len());
+        for &l in clause.iter().filter(|l| **l != 0) {
+            let v = l.unsigned_abs() as usize - 1;
+            self.reserve_vars(v + 1);
+            let lit = 2 * v + (l < 0) as usize;
+            if lits.contains(&neg(lit)) {
+                // Tautologies are always satisfied
+                return;
+            }
+            if !lits.contains(&lit) {
+                lits.push(lit);
+            }
+        }
+
+        // Literals already decided at level 0 can be simplified away
+        if lits.iter().any(|l| self.value(*l) == Some(true)) {
+            return;
+        }
+        lits.retain(|l| self.value(*l).is_none());
+
+        match lits.len() {
+            0 => self.inconsistent = true,
+            1 => {
+                self.enqueue(lits[0], None);
+                if self.propagate().is_some() {
+                    self.inconsistent = true;
+                }
+            },
+            _ => { self.attach(lits); }
+        }
+    }
+
+    /// Searches a satisfying assignment. The returned vector is indexed by
+    /// `variable - 1`.
+    pub fn solve(&mut self) -> Option<Vec<bool>> {
+        if self.inconsistent {
+            return None;
+        }
+        let result = self.search();
+        self.cancel_until(0);
+        if result.is_none() {
+            self.inconsistent = true;
+        }
+        result
+    }
+
+    fn search(&mut self) -> Option<Vec<bool>> {
+        let mut conflicts = 0_u64;
+        let mut restarts = 0_u32;
+        let mut restart_limit = 100 * luby(restarts);
+
+        loop {
+            if let Some(conflict) = self.propagate() {
+                conflicts += 1;
+                if self.trail_lim.is_empty() {
+                    return None;
+                }
+                let (learnt, backtrack_level) = self.analyze(conflict);
+                self.cancel_until(backtrack_level);
+
+                let asserting = learnt[0];
+                if learnt.len() == 1 {
+                    self.enqueue(asserting, None);
+                }
+                else {
+                    let index = self.attach(learnt);
+                    self.enqueue(asserting, Some(index));
+                }
+                self.decay_activities();
+
+                if conflicts >= restart_limit {
+                    conflicts = 0;
+                    restarts += 1;
+                    restart_limit = 100 * luby(restarts);
+                    self.cancel_until(0);
+                }
+            }
+            else {
+                match self.pick_branch_literal() {
+                    Some(lit) => {
+                        self.trail_lim.push(self.trail.len());
+                        self.enqueue(lit, None);
+                    },
+                    None => {
+                        return Some(self.assigns.iter().map(|a| a.unwrap_or(false)).collect());
+                    }
+                }
+            }
+        }
+    }
+
+    fn attach(&mut self, lits: Vec<Lit>) -> usize {
+        let index = self.clauses.len();
+        self.watches[lits[0]].push(index);
+        self.watches[lits[1]].push(index);
+        self.clauses.push(lits);
+        index
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.assigns[var(lit)].map(|v| v != (lit & 1 == 1))
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let v = var(lit);
+        self.assigns[v] = Some(lit & 1 == 0);
+        self.level[v] = self.trail_lim.len();
+        self.reason[v] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Propagates all the enqueued assignments, returning the conflicting
+    /// clause if any.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.qhead < self.trail.len() {
+            let false_lit = neg(self.trail[self.qhead]);
+            self.qhead += 1;
+
+            let watchers = std::mem::take(&mut self.watches[false_lit]);
+            let mut kept = Vec::with_capacity(watchers.len());
+            let mut conflict = None;
+
+            for (i, &ci) in watchers.iter().enumerate() {
+                if conflict.is_some() {
+                    kept.extend_from_slice(&watchers[i..]);
+                    break;
+                }
+                // Make sure the false literal is the second watch
+                if self.clauses[ci][0] == false_lit {
+                    self.clauses[ci].swap(0, 1);
+                }
+                let first = self.clauses[ci][0];
+                if self.value(first) == Some(true) {
+                    kept.push(ci);
+                    continue;
+                }
+
+                let replacement = (2..self.clauses[ci].len())
+                    .find(|&k| self.value(self.clauses[ci][k]) != Some(false));
+                if let Some(k) = replacement {
+                    self.clauses[ci].swap(1, k);
+                    let new_watch = self.clauses[ci][1];
+                    self.watches[new_watch].push(ci);
+                    continue;
+                }
+
+                kept.push(ci);
+                if self.value(first) == Some(false) {
+                    conflict = Some(ci);
+                }
+                else {
+                    self.enqueue(first, Some(ci));
+                }
+            }
+
+            self.watches[false_lit] = kept;
+            if conflict.is_some() {
+                self.qhead = self.trail.len();
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// First-UIP conflict analysis. Returns the learnt clause, with the
+    /// asserting literal first and a literal of the backtrack level second.
+    fn analyze(&mut self, mut conflict: usize) -> (Vec<Lit>, usize) {
+        let current_level = self.trail_lim.len();
+        let mut seen = vec![false; self.num_vars()];
+        let mut learnt: Vec<Lit> = vec![0];
+        let mut pending = 0;
+        let mut index = self.trail.len();
+        let mut implied: Option<Lit> = None;
+
+        loop {
+            // The implied literal of a reason clause is always the first one
+            let skip = if implied.is_some() { 1 } else { 0 };
+            for k in skip..self.clauses[conflict].len() {
+                let q = self.clauses[conflict][k];
+                let v = var(q);
+                if !seen[v] && self.level[v] > 0 {
+                    seen[v] = true;
+                    self.bump_activity(v);
+                    if self.level[v] == current_level {
+                        pending += 1;
+                    }
+                    else {
+                        learnt.push(q);
+                    }
+                }
+            }
+
+            loop {
+                index -= 1;
+                if seen[var(self.trail[index])] {
+                    break;
+                }
+            }
+            let p = self.trail[index];
+            seen[var(p)] = false;
+            pending -= 1;
+            implied = Some(p);
+
+            if pending == 0 {
+                break;
+            }
+            conflict = self.reason[var(p)].expect("only decisions lack a reason");
+        }
+
+        learnt[0] = neg(implied.expect("the conflict has literals of the current level"));
+
+        let mut backtrack_level = 0;
+        if learnt.len() > 1 {
+            let (k, _) = learnt.iter().enumerate().skip(1)
+                .max_by_key(|(_, l)| self.level[var(**l)])
+                .expect("the clause has more than one literal");
+            learnt.swap(1, k);
+            backtrack_level = self.level[var(learnt[1])];
+        }
+        (learnt, backtrack_level)
+    }
+
+    fn cancel_until(&mut self, level: usize) {
+        if self.trail_lim.len() <= level {
+            return;
+        }
+        let from = self.trail_lim[level];
+        for &lit in &self.trail[from..] {
+            let v = var(lit);
+            self.phase[v] = lit & 1 == 0;
+            self.assigns[v] = None;
+            self.reason[v] = None;
+        }
+        self.trail.truncate(from);
+        self.trail_lim.truncate(level);
+        self.qhead = self.trail.len();
+    }
+
+    fn pick_branch_literal(&self) -> Option<Lit> {
+        (0..self.num_vars())
+            .filter(|v| self.assigns[*v].is_none())
+            .max_by(|a, b| self.activity[*a].total_cmp(&self.activity[*b]).then(b.cmp(a)))
+            .map(|v| 2 * v + (!self.phase[v]) as usize)
+    }
+
+    fn bump_activity(&mut self, v: usize) {
+        self.activity[v] += self.var_inc;
+        if self.activity[v] > 1e100 {
+            self.activity.iter_mut().for_each(|a| *a *= 1e-100);
+            self.var_inc *= 1e-100;
+        }
+    }
+
+    fn decay_activities(&mut self) {
+        self.var_inc /= 0.95;
+    }
+}
+
+/// The Luby sequence: 1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8...
+fn luby(mut i: u32) -> u64 {
+    let mut size = 1_u64;
+    let mut seq = 0;
+    while size < i as u64 + 1 {
+        seq += 1;
+        size = 2 * size + 1;
+    }
+    while size - 1 != i as u64 {
+        size = (size - 1) >> 1;
+        seq -= 1;
+        i %= size as u32;
+    }
+    1 << seq
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn satisfies(model: &[bool], clauses: &[Vec<i64>]) -> bool {
+        clauses.iter().all(|clause| {
+            clause.iter().any(|l| model[l.unsigned_abs() as usize - 1] == (*l > 0))
+        })
+    }
+
+    fn brute_force(num_vars: usize, clauses: &[Vec<i64>]) -> bool {
+        (0..1_u32 << num_vars).any(|bits| {
+            let model: Vec<bool> = (0..num_vars).map(|i| bits & (1 << i) != 0).collect();
+            satisfies(&model, clauses)
+        })
+    }
+
+    /// Deterministic xorshift so the random instances are reproducible
+    fn random_instances(count: usize, num_vars: usize, num_clauses: usize) -> Vec<Vec<Vec<i64>>> {
+        let mut state = 0x2545F4914F6CDD1D_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..count).map(|_| {
+            (0..num_clauses).map(|_| {
+                (0..3).map(|_| {
+                    let v = (next() % num_vars as u64) as i64 + 1;
+                    if next() % 2 == 0 { v } else { -v }
+                }).collect()
+            }).collect()
+        }).collect()
+    }
+
+    #[test]
+    fn luby_sequence_is_correct() {
+        let sequence: Vec<u64> = (0..15).map(luby).collect();
+        assert_eq!(sequence, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn empty_and_contradicting_clauses_are_unsat() {
+        let mut solver = Solver::new();
+        solver.add_clause(&[]);
+        assert!(solver.solve().is_none());
+
+        let mut solver = Solver::new();
+        solver.add_clause(&[1]);
+        solver.add_clause(&[-1]);
+        assert!(solver.solve().is_none());
+
+        let mut solver = Solver::new();
+        assert_eq!(solver.solve(), Some(vec![]));
+    }
+
+    #[test]
+    fn pigeonhole_principle_is_unsat() {
+        // 5 pigeons in 4 holes, p(i, j) = pigeon i is in hole j
+        let (pigeons, holes) = (5, 4);
+        let p = |i: i64, j: i64| i * holes + j + 1;
+        let mut solver = Solver::new();
+        for i in 0..pigeons {
+            solver.add_clause(&(0..holes).map(|j| p(i, j)).collect::<Vec<_>>());
+        }
+        for j in 0..holes {
+            for a in 0..pigeons {
+                for b in (a + 1)..pigeons {
+                    solver.add_clause(&[-p(a, j), -p(b, j)]);
+                }
+            }
+        }
+        assert!(solver.solve().is_none());
+    }
+
+    #[test]
+    fn random_instances_agree_with_brute_force() {
+        for clauses in random_instances(200, 10, 43) {
+            let mut solver = Solver::new();
+            solver.reserve_vars(10);
+            clauses.iter().for_each(|c| solver.add_clause(c));
+
+            match solver.solve() {
+                Some(model) => assert!(satisfies(&model, &clauses)),
+                None => assert!(!brute_force(10, &clauses), "{clauses:?}")
+            }
+        }
+    }
+
+    #[test]
+    fn big_instances_are_solved() {
+        for clauses in random_instances(5, 150, 550) {
+            let mut solver = Solver::new();
+            clauses.iter().for_each(|c| solver.add_clause(c));
+            if let Some(model) = solver.solve() {
+                assert!(satisfies(&model, &clauses));
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::bdd::{Bdd, NodeId};
+use super::svg::Svg;
+
+static FONT_SIZE: u32 = 12;
+
+/// Renders the diagram rooted at `root`, one row per variable of the order
+/// and the terminals at the bottom.
+///
+/// Edges to the `high` child are solid and edges to the `low` child are
+/// dashed. `xsep`, `ysep` and `radius` have the same meaning as in
+/// [`render_to_svg`](super::render::render_to_svg).
+pub fn render_bdd_to_svg(bdd: &Bdd, root: NodeId, xsep: f32, ysep: f32, radius: f32) -> Svg {
+    let nodes = bdd.reachable(root);
+
+    // Rows sorted by variable, terminals go last
+    let mut rows: Vec<(usize, Vec<NodeId>)> = Vec::new();
+    for id in &nodes {
+        let level = bdd.node(*id).map(|n| n.var).unwrap_or(usize::MAX);
+        match rows.iter_mut().find(|(l, _)| *l == level) {
+            Some((_, row)) => row.push(*id),
+            None => rows.push((level, vec![*id]))
+        }
+    }
+    rows.sort_by_key(|(level, _)| *level);
+    for (_, row) in rows.iter_mut() {
+        row.sort();
+    }
+
+    let padding = radius + 1_f32;
+    let stroke_width = 1_f32;
+    let cell = 2_f32 * radius + xsep;
+    let widest = rows.iter().map(|(_, row)| row.len()).max().unwrap_or(1);
+    let width = widest as f32 * cell + (padding + stroke_width) * 2_f32 - xsep;
+    let height = ysep * (rows.len() - 1) as f32 + (padding + stroke_width) * 2_f32;
+
+    let position = |id: NodeId| {
+        let (y, row) = rows.iter().enumerate()
+            .find_map(|(y, (_, row))| row.iter().position(|n| *n == id).map(|x| (y, (x, row.len()))))
+            .expect("every reachable node has a row");
+        let (x, len) = row;
+        let row_width = len as f32 * cell - xsep;
+        let offset = (width - row_width) / 2_f32 + radius;
+        (offset + x as f32 * cell, padding + stroke_width + y as f32 * ysep)
+    };
+
+    let mut img = Svg::new((0_f32, 0_f32, width, height));
+    for id in nodes {
+        let pos = position(id);
+        match bdd.node(id) {
+            Some(node) => {
+                img.draw_circle_with_text(pos, radius, bdd.var_name(id).unwrap_or_default(), FONT_SIZE);
+                img.draw_dashed_line_with_offset(pos, position(node.low), radius);
+                img.draw_line_with_offset(pos, position(node.high), radius);
+            },
+            None => {
+                let text = if id == Bdd::TRUE { "1" } else { "0" };
+                img.draw_square_with_text(pos, radius * 0.8, text, FONT_SIZE);
+            }
+        }
+    }
+
+    img
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn svg_has_one_element_per_node_and_edge() -> Result<(), Box<dyn Error>> {
+        let tokens = crate::lexing::Lexer::new().tokenize("p & q")?;
+        let ast = crate::parsing::Parser::new(&tokens).parse()?;
+        let (bdd, root) = ast.to_bdd()?;
+
+        let xml = render_bdd_to_svg(&bdd, root, 20_f32, 30_f32, 15_f32).as_xml();
+
+        // 2 decision nodes (circle, text, 2 edges) and 2 terminals (rect, text)
+        assert_eq!(xml.lines().count(), 2 * 4 + 2 * 2);
+        assert_eq!(xml.matches("stroke-dasharray='4 3'").count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn variable_names_are_escaped() -> Result<(), Box<dyn Error>> {
+        let tokens = crate::lexing::Lexer::new().tokenize(r#""R&D <x>" & 'a"b'"#)?;
+        let ast = crate::parsing::Parser::new(&tokens).parse()?;
+        let (bdd, root) = ast.to_bdd()?;
+
+        let xml = render_bdd_to_svg(&bdd, root, 20_f32, 30_f32, 15_f32).as_xml();
+
+        assert!(xml.contains(">R&amp;D &lt;x&gt;</text>"));
+        assert!(xml.contains(">a&quot;b</text>"));
+        Ok(())
+    }
+}
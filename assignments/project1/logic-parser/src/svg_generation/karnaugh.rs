@@ -0,0 +1,90 @@
+use crate::minimizing::KarnaughMap;
+use super::svg::Svg;
+
+static FONT_SIZE: u32 = 12;
+/// Stroke colors of the groups, reused when there are more groups
+static GROUP_COLORS: [&str; 6] = ["crimson", "royalblue", "seagreen", "darkorange", "purple", "teal"];
+
+/// Renders the map as a grid of `cell`-sized squares with Gray code labels.
+///
+/// The row identifiers are written below the diagonal of the top-left corner
+/// and the column identifiers above it. Each group is outlined with rounded
+/// rectangles in its own color, split where it wraps around the edges.
+pub fn render_karnaugh_to_svg(map: &KarnaughMap, cell: f32) -> Svg {
+    let padding = 2_f32;
+    let width = cell * (map.column_count() + 1) as f32 + padding * 2_f32;
+    let height = cell * (map.row_count() + 1) as f32 + padding * 2_f32;
+    let origin = (padding + cell, padding + cell);
+    let (row_identifiers, column_identifiers) = map.identifiers.split_at(map.row_bits as usize);
+
+    let mut img = Svg::new((0_f32, 0_f32, width, height));
+    img.draw_line((padding, padding), origin);
+    img.draw_centered_text((padding + cell * 0.3, padding + cell * 0.7), &row_identifiers.join(""), FONT_SIZE);
+    img.draw_centered_text((padding + cell * 0.7, padding + cell * 0.3), &column_identifiers.join(""), FONT_SIZE);
+
+    for (column, label) in map.column_labels().iter().enumerate() {
+        img.draw_centered_text((origin.0 + cell * (column as f32 + 0.5), origin.1 - cell * 0.5), label, FONT_SIZE);
+    }
+    for (row, label) in map.row_labels().iter().enumerate() {
+        img.draw_centered_text((origin.0 - cell * 0.5, origin.1 + cell * (row as f32 + 0.5)), label, FONT_SIZE);
+    }
+
+    for (row, cells) in map.cells.iter().enumerate() {
+        for (column, value) in cells.iter().enumerate() {
+            let center = (origin.0 + cell * (column as f32 + 0.5), origin.1 + cell * (row as f32 + 0.5));
+            img.draw_square_with_text(center, cell / 2_f32, if *value { "1" } else { "0" }, FONT_SIZE);
+        }
+    }
+
+    for (i, group) in map.groups.iter().enumerate() {
+        img.stroke = GROUP_COLORS[i % GROUP_COLORS.len()];
+        // Nested groups stay visible
+        let inset = cell * (0.1 + 0.05 * (i % 3) as f32);
+        for (row, rows, column, columns) in group.rectangles() {
+            img.draw_rounded_rect(
+                (origin.0 + cell * column as f32 + inset, origin.1 + cell * row as f32 + inset),
+                (cell * columns as f32 - inset * 2_f32, cell * rows as f32 - inset * 2_f32),
+                cell / 4_f32
+            );
+        }
+    }
+    img.stroke = "black";
+
+    img
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn svg_has_cells_and_groups() -> Result<(), Box<dyn Error>> {
+        let tokens = crate::lexing::Lexer::new().tokenize("(a & b) | ~c")?;
+        let ast = crate::parsing::Parser::new(&tokens).parse()?;
+        let map = ast.to_karnaugh_map()?.with_groups();
+
+        let xml = render_karnaugh_to_svg(&map, 40_f32).as_xml();
+
+        // 8 cells, 6 labels and 2 identifier labels
+        assert_eq!(xml.matches("<text").count(), 8 + 6 + 2);
+        // ~c wraps around the columns
+        assert_eq!(map.groups.len(), 2);
+        assert_eq!(xml.matches(" rx=").count(), 3);
+        assert_eq!(xml.matches("<rect").count(), 8 + 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn identifier_labels_are_escaped() -> Result<(), Box<dyn Error>> {
+        let tokens = crate::lexing::Lexer::new().tokenize(r#""R&D <x>" & 'a"b'"#)?;
+        let ast = crate::parsing::Parser::new(&tokens).parse()?;
+
+        let xml = render_karnaugh_to_svg(&ast.to_karnaugh_map()?, 40_f32).as_xml();
+
+        assert!(xml.contains(">R&amp;D &lt;x&gt;</text>"));
+        assert!(xml.contains(">a&quot;b</text>"));
+        Ok(())
+    }
+}
@@ -1,2 +1,4 @@
 pub mod render;
 pub mod svg;
+pub mod bdd;
+pub mod karnaugh;
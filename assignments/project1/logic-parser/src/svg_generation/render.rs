@@ -4,16 +4,19 @@ use super::svg::Svg;
 /// Returns the tree depth (>= 1)
 fn ast_depth(ast: &ASTNode) -> u32 {
     match ast {
-        ASTNode::And { left, right } |
-        ASTNode::Or { left, right } |
-        ASTNode::Implies { left, right } |
-        ASTNode::IfAndOnlyIf { left, right } => {
-            let l = ast_depth(&left) + 1;
-            let r = ast_depth(&right) + 1;
+        ASTNode::And { left, right, .. } |
+        ASTNode::Or { left, right, .. } |
+        ASTNode::Implies { left, right, .. } |
+        ASTNode::IfAndOnlyIf { left, right, .. } |
+        ASTNode::Xor { left, right, .. } |
+        ASTNode::Nand { left, right, .. } |
+        ASTNode::Nor { left, right, .. } => {
+            let l = ast_depth(left) + 1;
+            let r = ast_depth(right) + 1;
             std::cmp::max(l, r)
         },
-        ASTNode::Not { operand } => {
-            ast_depth(&operand) + 1
+        ASTNode::Not { operand, .. } => {
+            ast_depth(operand) + 1
         },
         _ => 1
     }
@@ -65,10 +68,13 @@ pub fn render_to_svg(ast: ASTNode, xsep: f32, ysep: f32, radius: f32) -> Svg {
         img.draw_circle_with_text(pos, radius, node.repr(), FONT_SIZE);
 
         match node {
-            ASTNode::And { left, right } |
-            ASTNode::Or { left, right } |
-            ASTNode::Implies { left, right } |
-            ASTNode::IfAndOnlyIf { left, right } => {
+            ASTNode::And { left, right, .. } |
+            ASTNode::Or { left, right, .. } |
+            ASTNode::Implies { left, right, .. } |
+            ASTNode::IfAndOnlyIf { left, right, .. } |
+            ASTNode::Xor { left, right, .. } |
+            ASTNode::Nand { left, right, .. } |
+            ASTNode::Nor { left, right, .. } => {
                 stack.push((left, grid_x - next_step, grid_y + 1));
                 stack.push((right, grid_x + next_step, grid_y + 1));
 
@@ -77,7 +83,7 @@ pub fn render_to_svg(ast: ASTNode, xsep: f32, ysep: f32, radius: f32) -> Svg {
                 img.draw_line_with_offset(pos, left_to, radius);
                 img.draw_line_with_offset(pos, right_to, radius);
             },
-             ASTNode::Not { operand } => {
+             ASTNode::Not { operand, .. } => {
                 stack.push((operand, grid_x, grid_y + 1));
                 img.draw_line_with_offset(pos, get_real_xy(grid_x, grid_y + 1), radius);
             },
@@ -114,4 +120,17 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn identifiers_are_escaped() -> Result<(), Box<dyn Error>> {
+        let tokens = crate::lexing::Lexer::new().tokenize(r#""R&D <x>" & 'a"b'"#)?;
+        let ast = crate::parsing::Parser::new(&tokens).parse()?;
+
+        let xml = render_to_svg(ast, 20_f32, 30_f32, 15_f32).as_xml();
+
+        assert!(xml.contains(">R&amp;D &lt;x&gt;</text>"));
+        assert!(xml.contains(">a&quot;b</text>"));
+        assert!(!xml.contains("<x>"));
+        Ok(())
+    }
 }
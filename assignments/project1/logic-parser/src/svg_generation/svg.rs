@@ -58,6 +58,72 @@ impl Svg {
         );
     }
 
+    /// Like [`Svg::draw_line_with_offset`] but the line is dashed.
+    pub fn draw_dashed_line_with_offset(&mut self, start: (f32, f32), end: (f32, f32), offset: f32) {
+        let line_length = f32::sqrt(f32::powi(end.0 - start.0, 2) + f32::powi(end.1 - start.1, 2));
+        let (dx, dy) = ((end.0 - start.0) / line_length, (end.1 - start.1) / line_length);
+
+        self.elements.push(
+            format!(
+                "<line x1='{x1}' y1='{y1}' x2='{x2}' y2='{y2}' stroke='{stroke}' stroke-dasharray='4 3' />",
+                x1 = start.0 + dx * offset,
+                y1 = start.1 + dy * offset,
+                x2 = end.0 - dx * offset,
+                y2 = end.1 - dy * offset,
+                stroke = self.stroke,
+            )
+        );
+    }
+
+    pub fn draw_square_with_text(&mut self, center: (f32, f32), half_side: f32, text: &str, font_size: u32) {
+        self.elements.push(
+            format!(
+                "<rect x='{x}' y='{y}' width='{side}' height='{side}' fill='none' stroke='{stroke}' />",
+                x = center.0 - half_side,
+                y = center.1 - half_side,
+                side = 2_f32 * half_side,
+                stroke = self.stroke,
+            )
+        );
+        self.elements.push(
+            format!(
+                "<text x='{x}' y='{y}' font-family='{font}' font-size='{font_size}' text-anchor='middle' alignment-baseline='central'>{text}</text>",
+                x = center.0,
+                y = center.1,
+                font = self.font,
+                text = escape_xml(text),
+                font_size = font_size
+            )
+        );
+    }
+
+    pub fn draw_rounded_rect(&mut self, pos: (f32, f32), size: (f32, f32), corner_radius: f32) {
+        self.elements.push(
+            format!(
+                "<rect x='{x}' y='{y}' width='{w}' height='{h}' rx='{r}' fill='none' stroke='{stroke}' />",
+                x = pos.0,
+                y = pos.1,
+                w = size.0,
+                h = size.1,
+                r = corner_radius,
+                stroke = self.stroke,
+            )
+        );
+    }
+
+    pub fn draw_centered_text(&mut self, pos: (f32, f32), text: &str, font_size: u32) {
+        self.elements.push(
+            format!(
+                "<text x='{x}' y='{y}' font-family='{font}' font-size='{font_size}' text-anchor='middle' alignment-baseline='central'>{text}</text>",
+                x = pos.0,
+                y = pos.1,
+                font = self.font,
+                text = escape_xml(text),
+                font_size = font_size
+            )
+        );
+    }
+
     pub fn draw_text(&mut self, pos: (f32, f32), text: &str, font_size: u32) {
         self.elements.push(
             format!(
@@ -65,7 +131,7 @@ impl Svg {
                 x = pos.0,
                 y = pos.1,
                 font = self.font,
-                text = text,
+                text = escape_xml(text),
                 font_size = font_size
             )
         );
@@ -79,7 +145,7 @@ impl Svg {
                 x = pos.0,
                 y = pos.1,
                 font = self.font,
-                text = text,
+                text = escape_xml(text),
                 font_size = font_size
             )
         );
@@ -87,10 +153,30 @@ impl Svg {
 
     pub fn as_xml(&self) -> String {
         format!(
-            "<svg xmlns='http://www.w3.org/2000/svg' viewBox='{vb}' stroke-width='{sw}'>{elements}</svg>",
-            vb = format!("{x} {y} {w} {h}", x = self.view_box.0, y = self.view_box.1, w = self.view_box.2, h = self.view_box.3),
+            "<svg xmlns='http://www.w3.org/2000/svg' viewBox='{x} {y} {w} {h}' stroke-width='{sw}'>{elements}</svg>",
+            x = self.view_box.0,
+            y = self.view_box.1,
+            w = self.view_box.2,
+            h = self.view_box.3,
             sw = self.stroke_width,
             elements = self.elements.join("\n")
         )
     }
 }
+
+/// Escapes the characters of `text` that can't appear in XML text or
+/// attributes, identifiers can contain any of them.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
@@ -0,0 +1,9 @@
+mod normal_forms;
+mod rewriting;
+mod simplify;
+mod tseitin;
+
+pub use normal_forms::*;
+pub use rewriting::*;
+pub use simplify::*;
+pub use tseitin::*;
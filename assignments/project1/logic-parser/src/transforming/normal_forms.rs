@@ -0,0 +1,398 @@
+use std::collections::HashSet;
+use crate::errors::TransformError;
+use crate::parsing::ASTNode;
+
+pub type Result<T> = std::result::Result<T, TransformError>;
+
+/// An identifier or its negation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Literal {
+    pub name: String,
+    pub negated: bool
+}
+
+impl Literal {
+    pub fn new<S: Into<String>>(name: S, negated: bool) -> Self {
+        Literal { name: name.into(), negated }
+    }
+
+    pub fn negate(&self) -> Literal {
+        Literal { name: self.name.clone(), negated: !self.negated }
+    }
+
+    pub fn to_ast(&self) -> ASTNode {
+        let identifier = ASTNode::Identifier { name: self.name.clone(), span: None };
+        if self.negated {
+            ASTNode::Not { operand: Box::new(identifier), span: None }
+        }
+        else {
+            identifier
+        }
+    }
+}
+
+/// Conjunctive normal form: a conjunction of clauses, each clause being a
+/// disjunction of literals.
+///
+/// When built from an expression, no clause is repeated, no literal is
+/// repeated inside a clause and clauses containing both a literal and its
+/// negation are dropped. An expression without clauses is `true`, an empty
+/// clause is `false`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cnf {
+    pub clauses: Vec<Vec<Literal>>
+}
+
+/// Disjunctive normal form: a disjunction of terms, each term being a
+/// conjunction of literals.
+///
+/// The dual of [`Cnf`]: an expression without terms is `false`, an empty term
+/// is `true`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dnf {
+    pub terms: Vec<Vec<Literal>>
+}
+
+impl Cnf {
+    /// Converts the expression by distributing `∨` over `∧`. The result can be
+    /// exponentially bigger than the input.
+    ///
+    /// Fails if the expression has error nodes.
+    pub fn from_ast(ast: &ASTNode) -> Result<Self> {
+        let clauses = distribute(&ast.to_nnf(), Form::Conjunctive, None)?;
+        Ok(Cnf { clauses })
+    }
+
+    /// Like [`Cnf::from_ast`] but gives up as soon as the amount of clauses
+    /// exceeds `max_clauses`.
+    pub fn from_ast_bounded(ast: &ASTNode, max_clauses: usize) -> Result<Self> {
+        let clauses = distribute(&ast.to_nnf(), Form::Conjunctive, Some(max_clauses))?;
+        Ok(Cnf { clauses })
+    }
+
+    pub fn to_ast(&self) -> ASTNode {
+        join(&self.clauses, Form::Conjunctive)
+    }
+}
+
+impl Dnf {
+    /// Converts the expression by distributing `∧` over `∨`. The result can be
+    /// exponentially bigger than the input.
+    ///
+    /// Fails if the expression has error nodes.
+    pub fn from_ast(ast: &ASTNode) -> Result<Self> {
+        let terms = distribute(&ast.to_nnf(), Form::Disjunctive, None)?;
+        Ok(Dnf { terms })
+    }
+
+    /// Like [`Dnf::from_ast`] but gives up as soon as the amount of terms
+    /// exceeds `max_terms`.
+    pub fn from_ast_bounded(ast: &ASTNode, max_terms: usize) -> Result<Self> {
+        let terms = distribute(&ast.to_nnf(), Form::Disjunctive, Some(max_terms))?;
+        Ok(Dnf { terms })
+    }
+
+    pub fn to_ast(&self) -> ASTNode {
+        join(&self.terms, Form::Disjunctive)
+    }
+}
+
+impl ASTNode {
+    /// Returns the negation normal form of the expression: only `∧`, `∨` and
+    /// negations of identifiers remain.
+    ///
+    /// `a ⟷ b` is expanded as `(¬a ∨ b) ∧ (a ∨ ¬b)` and negated literals are
+    /// folded (`¬true` becomes `false`). Error nodes are kept as they are.
+    pub fn to_nnf(&self) -> ASTNode {
+        nnf(self, false)
+    }
+
+    /// Returns the conjunctive normal form of the expression.
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::Parser;
+    ///
+    /// let tokens = Lexer::new().tokenize("p | (q & r)").unwrap();
+    /// let ast = Parser::new(&tokens).parse().unwrap();
+    ///
+    /// assert_eq!(ast.to_cnf().unwrap().to_string(), "(p | q) & (p | r)");
+    /// ```
+    pub fn to_cnf(&self) -> Result<ASTNode> {
+        Ok(Cnf::from_ast(self)?.to_ast())
+    }
+
+    /// Like [`ASTNode::to_cnf`] but fails if the result has more than
+    /// `max_clauses` clauses.
+    pub fn to_cnf_bounded(&self, max_clauses: usize) -> Result<ASTNode> {
+        Ok(Cnf::from_ast_bounded(self, max_clauses)?.to_ast())
+    }
+
+    /// Returns the disjunctive normal form of the expression.
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::Parser;
+    ///
+    /// let tokens = Lexer::new().tokenize("p & (q -> r)").unwrap();
+    /// let ast = Parser::new(&tokens).parse().unwrap();
+    ///
+    /// assert_eq!(ast.to_dnf().unwrap().to_string(), "p & ~q | p & r");
+    /// ```
+    pub fn to_dnf(&self) -> Result<ASTNode> {
+        Ok(Dnf::from_ast(self)?.to_ast())
+    }
+
+    /// Like [`ASTNode::to_dnf`] but fails if the result has more than
+    /// `max_terms` terms.
+    pub fn to_dnf_bounded(&self, max_terms: usize) -> Result<ASTNode> {
+        Ok(Dnf::from_ast_bounded(self, max_terms)?.to_ast())
+    }
+
+    pub fn is_nnf(&self) -> bool {
+        match self {
+            ASTNode::Identifier { .. } | ASTNode::Literal { .. } => true,
+            ASTNode::Not { operand, .. } => matches!(**operand, ASTNode::Identifier { .. }),
+            ASTNode::And { left, right, .. } | ASTNode::Or { left, right, .. } => left.is_nnf() && right.is_nnf(),
+            _ => false
+        }
+    }
+
+    pub fn is_cnf(&self) -> bool {
+        match self {
+            ASTNode::And { left, right, .. } => left.is_cnf() && right.is_cnf(),
+            other => other.is_flat(Form::Disjunctive)
+        }
+    }
+
+    pub fn is_dnf(&self) -> bool {
+        match self {
+            ASTNode::Or { left, right, .. } => left.is_dnf() && right.is_dnf(),
+            other => other.is_flat(Form::Conjunctive)
+        }
+    }
+
+    /// Whether the node is a clause (for [`Form::Disjunctive`]) or a term (for
+    /// [`Form::Conjunctive`]).
+    fn is_flat(&self, form: Form) -> bool {
+        match (self, form) {
+            (ASTNode::Or { left, right, .. }, Form::Disjunctive) |
+            (ASTNode::And { left, right, .. }, Form::Conjunctive) => {
+                left.is_flat(form) && right.is_flat(form)
+            },
+            (ASTNode::Identifier { .. } | ASTNode::Literal { .. }, _) => true,
+            (ASTNode::Not { operand, .. }, _) => matches!(**operand, ASTNode::Identifier { .. }),
+            _ => false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Form {
+    Conjunctive,
+    Disjunctive
+}
+
+fn nnf(node: &ASTNode, negated: bool) -> ASTNode {
+    let and = |l, r| ASTNode::And { left: Box::new(l), right: Box::new(r), span: None };
+    let or = |l, r| ASTNode::Or { left: Box::new(l), right: Box::new(r), span: None };
+
+    match node {
+        ASTNode::Identifier { .. } | ASTNode::Error { .. } => {
+            if negated { ASTNode::Not { operand: Box::new(node.clone()), span: None } } else { node.clone() }
+        },
+        ASTNode::Literal { value, .. } => ASTNode::Literal { value: *value != negated, span: None },
+        ASTNode::Not { operand, .. } => nnf(operand, !negated),
+        ASTNode::And { left, right, .. } => {
+            if negated { or(nnf(left, true), nnf(right, true)) } else { and(nnf(left, false), nnf(right, false)) }
+        },
+        ASTNode::Or { left, right, .. } => {
+            if negated { and(nnf(left, true), nnf(right, true)) } else { or(nnf(left, false), nnf(right, false)) }
+        },
+        ASTNode::Implies { left, right, .. } => {
+            // a → b ≡ ¬a ∨ b
+            if negated { and(nnf(left, false), nnf(right, true)) } else { or(nnf(left, true), nnf(right, false)) }
+        },
+        ASTNode::IfAndOnlyIf { left, right, .. } => {
+            // a ⟷ b ≡ (¬a ∨ b) ∧ (a ∨ ¬b), ¬(a ⟷ b) ≡ (a ∨ b) ∧ (¬a ∨ ¬b)
+            and(
+                or(nnf(left, !negated), nnf(right, false)),
+                or(nnf(left, negated), nnf(right, true))
+            )
+        },
+        ASTNode::Xor { left, right, .. } => {
+            // a ⊕ b ≡ ¬(a ⟷ b) ≡ (a ∨ b) ∧ (¬a ∨ ¬b)
+            and(
+                or(nnf(left, negated), nnf(right, false)),
+                or(nnf(left, !negated), nnf(right, true))
+            )
+        },
+        ASTNode::Nand { left, right, .. } => {
+            // a ↑ b ≡ ¬a ∨ ¬b
+            if negated { and(nnf(left, false), nnf(right, false)) } else { or(nnf(left, true), nnf(right, true)) }
+        },
+        ASTNode::Nor { left, right, .. } => {
+            // a ↓ b ≡ ¬a ∧ ¬b
+            if negated { or(nnf(left, false), nnf(right, false)) } else { and(nnf(left, true), nnf(right, true)) }
+        }
+    }
+}
+
+/// Computes the clauses (or terms) of an expression already in NNF.
+///
+/// For the conjunctive form `∧` concatenates clauses and `∨` distributes,
+/// the disjunctive form is the dual.
+fn distribute(node: &ASTNode, form: Form, limit: Option<usize>) -> Result<Vec<Vec<Literal>>> {
+    let check = |groups: Vec<Vec<Literal>>| match limit {
+        Some(max) if groups.len() > max => Err(TransformError::ClauseLimitExceeded(max)),
+        _ => Ok(groups)
+    };
+
+    let groups = match (node, form) {
+        (ASTNode::Error { span }, _) => return Err(TransformError::ErrorNode(*span)),
+        (ASTNode::Identifier { name, .. }, _) => vec![vec![Literal::new(name, false)]],
+        (ASTNode::Not { operand, .. }, _) => match &**operand {
+            ASTNode::Identifier { name, .. } => vec![vec![Literal::new(name, true)]],
+            ASTNode::Error { span } => return Err(TransformError::ErrorNode(*span)),
+            _ => unreachable!("the expression is in negation normal form")
+        },
+        // The neutral element of the outer operator has no groups, the
+        // absorbing one has a single empty group
+        (ASTNode::Literal { value, .. }, Form::Conjunctive) => if *value { vec![] } else { vec![vec![]] },
+        (ASTNode::Literal { value, .. }, Form::Disjunctive) => if *value { vec![vec![]] } else { vec![] },
+        (ASTNode::And { left, right, .. }, Form::Conjunctive) |
+        (ASTNode::Or { left, right, .. }, Form::Disjunctive) => {
+            let mut groups = distribute(left, form, limit)?;
+            groups.extend(distribute(right, form, limit)?);
+            dedup_groups(groups)
+        },
+        (ASTNode::Or { left, right, .. }, Form::Conjunctive) |
+        (ASTNode::And { left, right, .. }, Form::Disjunctive) => {
+            let l = distribute(left, form, limit)?;
+            let r = distribute(right, form, limit)?;
+            let mut groups = Vec::new();
+            for a in &l {
+                for b in &r {
+                    let mut merged = a.clone();
+                    for literal in b {
+                        if !merged.contains(literal) {
+                            merged.push(literal.clone());
+                        }
+                    }
+                    // Complementary clauses are always true (and terms false)
+                    if !merged.iter().any(|lit| merged.contains(&lit.negate())) {
+                        groups.push(merged);
+                    }
+                }
+                // Bail out early, before the cartesian product blows up
+                if limit.is_some() {
+                    groups = check(dedup_groups(groups))?;
+                }
+            }
+            dedup_groups(groups)
+        },
+        _ => unreachable!("the expression is in negation normal form")
+    };
+
+    check(groups)
+}
+
+fn dedup_groups(groups: Vec<Vec<Literal>>) -> Vec<Vec<Literal>> {
+    let mut seen = HashSet::new();
+    groups.into_iter().filter(|group| {
+        let mut key = group.clone();
+        key.sort();
+        seen.insert(key)
+    }).collect()
+}
+
+fn join(groups: &[Vec<Literal>], form: Form) -> ASTNode {
+    let and = |l, r| ASTNode::And { left: Box::new(l), right: Box::new(r), span: None };
+    let or = |l, r| ASTNode::Or { left: Box::new(l), right: Box::new(r), span: None };
+    let conjunctive = form == Form::Conjunctive;
+
+    groups.iter()
+        .map(|group| {
+            group.iter()
+                .map(Literal::to_ast)
+                .reduce(|l, r| if conjunctive { or(l, r) } else { and(l, r) })
+                // An empty clause is false and an empty term is true
+                .unwrap_or(ASTNode::Literal { value: !conjunctive, span: None })
+        })
+        .reduce(|l, r| if conjunctive { and(l, r) } else { or(l, r) })
+        .unwrap_or(ASTNode::Literal { value: conjunctive, span: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::testing::{parse, parse_recovered};
+
+    const EXPRESSIONS: [&str; 8] = [
+        "((p || q)) => (q && ~(r))",
+        "~(a <-> b) | c",
+        "(a <-> b) <-> c",
+        "~(p -> ~(q & true))",
+        "(a & b) | (c & d) | (e & f)",
+        "p & ~p",
+        "(a ^ b) ^ ~c",
+        "~(p nand q) | (q nor ~r)",
+    ];
+
+    #[test]
+    fn nnf_is_equivalent() {
+        for expression in EXPRESSIONS {
+            let ast = parse(expression);
+            let nnf = ast.to_nnf();
+            assert!(nnf.is_nnf(), "{nnf}");
+            assert!(nnf.is_equivalent_to(&ast).unwrap(), "{expression}");
+        }
+        assert_eq!(parse("~(p -> ~(q & true))").to_nnf().to_string(), "p & (q & true)");
+    }
+
+    #[test]
+    fn cnf_and_dnf_are_equivalent() {
+        for expression in EXPRESSIONS {
+            let ast = parse(expression);
+            let (cnf, dnf) = (ast.to_cnf().unwrap(), ast.to_dnf().unwrap());
+            assert!(cnf.is_cnf(), "{cnf}");
+            assert!(dnf.is_dnf(), "{dnf}");
+            assert!(cnf.is_equivalent_to(&ast).unwrap(), "{expression}");
+            assert!(dnf.is_equivalent_to(&ast).unwrap(), "{expression}");
+        }
+    }
+
+    #[test]
+    fn trivial_clauses_are_removed() {
+        assert_eq!(parse("p | ~p").to_cnf().unwrap(), ASTNode::Literal { value: true, span: None });
+        assert_eq!(parse("p & ~p").to_dnf().unwrap(), ASTNode::Literal { value: false, span: None });
+        assert_eq!(parse("(p | q) & (q | p)").to_cnf().unwrap().to_string(), "p | q");
+    }
+
+    #[test]
+    fn bounded_conversion_fails_when_exceeded() {
+        let ast = parse("(a & b) | (c & d) | (e & f)");
+        assert_eq!(Cnf::from_ast(&ast).unwrap().clauses.len(), 8);
+        assert!(ast.to_cnf_bounded(8).is_ok());
+        match ast.to_cnf_bounded(7).unwrap_err() {
+            TransformError::ClauseLimitExceeded(limit) => assert_eq!(limit, 7),
+            _ => unreachable!()
+        }
+        assert!(ast.to_dnf_bounded(3).is_ok());
+    }
+
+    #[test]
+    fn error_nodes_result_on_error() {
+        let ast = parse_recovered("~(p & $)");
+        assert_eq!(ast.to_nnf().to_string(), "~p | ~<error>");
+        assert!(matches!(ast.to_cnf_bounded(10), Err(TransformError::ErrorNode(Some(_)))));
+        assert!(matches!(ast.to_dnf_bounded(10), Err(TransformError::ErrorNode(Some(_)))));
+        assert!(matches!(ast.to_cnf(), Err(TransformError::ErrorNode(Some(_)))));
+        assert!(matches!(ast.to_dnf(), Err(TransformError::ErrorNode(Some(_)))));
+        assert!(matches!(Cnf::from_ast(&ast), Err(TransformError::ErrorNode(Some(_)))));
+        assert!(matches!(Dnf::from_ast(&ast), Err(TransformError::ErrorNode(Some(_)))));
+    }
+}
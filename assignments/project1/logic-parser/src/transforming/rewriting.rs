@@ -0,0 +1,573 @@
+use std::collections::HashSet;
+use crate::parsing::ASTNode;
+
+/// Named logical equivalences that can be used to rewrite an expression.
+///
+/// Every law is applied from left to right as written in its documentation,
+/// with the operands of the commutative operators matched in any order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Law {
+    /// `a → b ≡ ¬a ∨ b`
+    ImplicationElimination,
+    /// `a ⟷ b ≡ (a → b) ∧ (b → a)`
+    BiconditionalElimination,
+    /// `a ⊕ b ≡ ¬(a ⟷ b)`, `a ↑ b ≡ ¬(a ∧ b)` and `a ↓ b ≡ ¬(a ∨ b)`
+    DerivedOperatorElimination,
+    /// `¬¬a ≡ a`
+    DoubleNegation,
+    /// `¬(a ∧ b) ≡ ¬a ∨ ¬b` and `¬(a ∨ b) ≡ ¬a ∧ ¬b`
+    DeMorgan,
+    /// `a ∨ (b ∧ c) ≡ (a ∨ b) ∧ (a ∨ c)`
+    OrDistribution,
+    /// `a ∧ (b ∨ c) ≡ (a ∧ b) ∨ (a ∧ c)`
+    AndDistribution,
+    /// `a ∧ (a ∨ b) ≡ a` and `a ∨ (a ∧ b) ≡ a`
+    Absorption,
+    /// `a ∧ a ≡ a` and `a ∨ a ≡ a`
+    Idempotence,
+    /// `a ∧ b ≡ b ∧ a`, `a ∨ b ≡ b ∨ a` and the same for `⟷`, `⊕`, `↑` and `↓`
+    Commutativity,
+    /// `a ∧ ¬a ≡ false`, `a ∨ ¬a ≡ true`, `¬true ≡ false` and `¬false ≡ true`
+    Negation,
+    /// `a ∧ true ≡ a` and `a ∨ false ≡ a`
+    Identity,
+    /// `a ∧ false ≡ false` and `a ∨ true ≡ true`
+    Domination,
+}
+
+impl Law {
+    pub const ALL: [Law; 13] = [
+        Law::ImplicationElimination,
+        Law::BiconditionalElimination,
+        Law::DerivedOperatorElimination,
+        Law::DoubleNegation,
+        Law::DeMorgan,
+        Law::OrDistribution,
+        Law::AndDistribution,
+        Law::Absorption,
+        Law::Idempotence,
+        Law::Commutativity,
+        Law::Negation,
+        Law::Identity,
+        Law::Domination,
+    ];
+
+    /// Human readable name of the law.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Law::ImplicationElimination => "Implication elimination",
+            Law::BiconditionalElimination => "Biconditional elimination",
+            Law::DerivedOperatorElimination => "Derived operator elimination",
+            Law::DoubleNegation => "Double negation",
+            Law::DeMorgan => "De Morgan's law",
+            Law::OrDistribution => "Distribution of ∨ over ∧",
+            Law::AndDistribution => "Distribution of ∧ over ∨",
+            Law::Absorption => "Absorption",
+            Law::Idempotence => "Idempotence",
+            Law::Commutativity => "Commutativity",
+            Law::Negation => "Negation",
+            Law::Identity => "Identity",
+            Law::Domination => "Domination",
+        }
+    }
+
+    /// Identifier of the law, as used in the JSON output.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Law::ImplicationElimination => "implication_elimination",
+            Law::BiconditionalElimination => "biconditional_elimination",
+            Law::DerivedOperatorElimination => "derived_operator_elimination",
+            Law::DoubleNegation => "double_negation",
+            Law::DeMorgan => "de_morgan",
+            Law::OrDistribution => "or_distribution",
+            Law::AndDistribution => "and_distribution",
+            Law::Absorption => "absorption",
+            Law::Idempotence => "idempotence",
+            Law::Commutativity => "commutativity",
+            Law::Negation => "negation",
+            Law::Identity => "identity",
+            Law::Domination => "domination",
+        }
+    }
+
+    /// Rewrites the root of `node` using the law, or returns `None` if the law
+    /// doesn't match. Laws never match expressions with error nodes.
+    pub fn apply(&self, node: &ASTNode) -> Option<ASTNode> {
+        use ASTNode::*;
+        if node.has_errors() {
+            return None;
+        }
+        let b = |n: &ASTNode| Box::new(n.clone());
+        let is_negation_of = |a: &ASTNode, b: &ASTNode| matches!(b, Not { operand, .. } if **operand == *a);
+
+        match (self, node) {
+            (Law::ImplicationElimination, Implies { left, right, .. }) => {
+                Some(Or { left: Box::new(Not { operand: b(left), span: None }), right: b(right), span: None })
+            },
+            (Law::BiconditionalElimination, IfAndOnlyIf { left, right, .. }) => {
+                Some(And {
+                    left: Box::new(Implies { left: b(left), right: b(right), span: None }),
+                    right: Box::new(Implies { left: b(right), right: b(left), span: None }),
+                    span: None
+                })
+            },
+            (Law::DerivedOperatorElimination, Xor { left, right, .. }) => {
+                Some(Not { operand: Box::new(IfAndOnlyIf { left: b(left), right: b(right), span: None }), span: None })
+            },
+            (Law::DerivedOperatorElimination, Nand { left, right, .. }) => {
+                Some(Not { operand: Box::new(And { left: b(left), right: b(right), span: None }), span: None })
+            },
+            (Law::DerivedOperatorElimination, Nor { left, right, .. }) => {
+                Some(Not { operand: Box::new(Or { left: b(left), right: b(right), span: None }), span: None })
+            },
+            (Law::DoubleNegation, Not { operand, .. }) => match &**operand {
+                Not { operand, .. } => Some(*operand.clone()),
+                _ => None
+            },
+            (Law::DeMorgan, Not { operand, .. }) => match &**operand {
+                And { left, right, .. } => Some(Or {
+                    left: Box::new(Not { operand: b(left), span: None }),
+                    right: Box::new(Not { operand: b(right), span: None }),
+                    span: None
+                }),
+                Or { left, right, .. } => Some(And {
+                    left: Box::new(Not { operand: b(left), span: None }),
+                    right: Box::new(Not { operand: b(right), span: None }),
+                    span: None
+                }),
+                _ => None
+            },
+            (Law::OrDistribution, Or { left, right, .. }) => match (&**left, &**right) {
+                (a, And { left: x, right: y, .. }) => Some(And {
+                    left: Box::new(Or { left: b(a), right: b(x), span: None }),
+                    right: Box::new(Or { left: b(a), right: b(y), span: None }),
+                    span: None
+                }),
+                (And { left: x, right: y, .. }, a) => Some(And {
+                    left: Box::new(Or { left: b(x), right: b(a), span: None }),
+                    right: Box::new(Or { left: b(y), right: b(a), span: None }),
+                    span: None
+                }),
+                _ => None
+            },
+            (Law::AndDistribution, And { left, right, .. }) => match (&**left, &**right) {
+                (a, Or { left: x, right: y, .. }) => Some(Or {
+                    left: Box::new(And { left: b(a), right: b(x), span: None }),
+                    right: Box::new(And { left: b(a), right: b(y), span: None }),
+                    span: None
+                }),
+                (Or { left: x, right: y, .. }, a) => Some(Or {
+                    left: Box::new(And { left: b(x), right: b(a), span: None }),
+                    right: Box::new(And { left: b(y), right: b(a), span: None }),
+                    span: None
+                }),
+                _ => None
+            },
+            (Law::Absorption, And { left, right, .. }) => {
+                let absorbs = |a: &ASTNode, other: &ASTNode| matches!(
+                    other, Or { left, right, .. } if **left == *a || **right == *a
+                );
+                if absorbs(left, right) { Some(*left.clone()) }
+                else if absorbs(right, left) { Some(*right.clone()) }
+                else { None }
+            },
+            (Law::Absorption, Or { left, right, .. }) => {
+                let absorbs = |a: &ASTNode, other: &ASTNode| matches!(
+                    other, And { left, right, .. } if **left == *a || **right == *a
+                );
+                if absorbs(left, right) { Some(*left.clone()) }
+                else if absorbs(right, left) { Some(*right.clone()) }
+                else { None }
+            },
+            (Law::Idempotence, And { left, right, .. } | Or { left, right, .. }) => {
+                (left == right).then(|| *left.clone())
+            },
+            (Law::Commutativity, And { left, right, .. }) => Some(And { left: b(right), right: b(left), span: None }),
+            (Law::Commutativity, Or { left, right, .. }) => Some(Or { left: b(right), right: b(left), span: None }),
+            (Law::Commutativity, IfAndOnlyIf { left, right, .. }) => {
+                Some(IfAndOnlyIf { left: b(right), right: b(left), span: None })
+            },
+            (Law::Commutativity, Xor { left, right, .. }) => Some(Xor { left: b(right), right: b(left), span: None }),
+            (Law::Commutativity, Nand { left, right, .. }) => Some(Nand { left: b(right), right: b(left), span: None }),
+            (Law::Commutativity, Nor { left, right, .. }) => Some(Nor { left: b(right), right: b(left), span: None }),
+            (Law::Negation, And { left, right, .. } | Or { left, right, .. }) => {
+                let complementary = is_negation_of(left, right) || is_negation_of(right, left);
+                complementary.then_some(Literal { value: matches!(node, Or { .. }), span: None })
+            },
+            (Law::Negation, Not { operand, .. }) => match **operand {
+                Literal { value, .. } => Some(Literal { value: !value, span: None }),
+                _ => None
+            },
+            (Law::Identity, And { left, right, .. }) => match (&**left, &**right) {
+                (Literal { value: true, .. }, other) | (other, Literal { value: true, .. }) => Some(other.clone()),
+                _ => None
+            },
+            (Law::Identity, Or { left, right, .. }) => match (&**left, &**right) {
+                (Literal { value: false, .. }, other) | (other, Literal { value: false, .. }) => Some(other.clone()),
+                _ => None
+            },
+            (Law::Domination, And { left, right, .. }) => {
+                let dominated = matches!(**left, Literal { value: false, .. }) || matches!(**right, Literal { value: false, .. });
+                dominated.then_some(Literal { value: false, span: None })
+            },
+            (Law::Domination, Or { left, right, .. }) => {
+                let dominated = matches!(**left, Literal { value: true, .. }) || matches!(**right, Literal { value: true, .. });
+                dominated.then_some(Literal { value: true, span: None })
+            },
+            _ => None
+        }
+    }
+
+    /// Rewrites the subterm of `ast` found at `path` using the law.
+    ///
+    /// See [`ASTNode::subterm`] for how paths are described.
+    pub fn apply_at(&self, ast: &ASTNode, path: &[usize]) -> Option<Step> {
+        let before = ast.subterm(path)?;
+        // The rewritten subterm stands for the same range of the source
+        let after = self.apply(before)?.with_span(before.span());
+        let expression = ast.replace_subterm(path, after.clone())?;
+
+        Some(Step {
+            law: *self,
+            path: path.to_vec(),
+            before: before.clone(),
+            after,
+            expression
+        })
+    }
+}
+
+impl std::fmt::Display for Law {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A single rewrite of a [`Derivation`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Step {
+    pub law: Law,
+    /// Location of the rewritten subterm, see [`ASTNode::subterm`]
+    pub path: Vec<usize>,
+    /// The subterm before applying the law
+    pub before: ASTNode,
+    /// The subterm after applying the law
+    pub after: ASTNode,
+    /// The whole expression after applying the law
+    pub expression: ASTNode
+}
+
+/// Trace of the laws applied to an expression, from the initial expression to
+/// the result.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Derivation {
+    pub initial: ASTNode,
+    pub steps: Vec<Step>
+}
+
+impl Derivation {
+    /// The expression after the last step.
+    pub fn result(&self) -> &ASTNode {
+        self.steps.last().map(|s| &s.expression).unwrap_or(&self.initial)
+    }
+
+    #[cfg(not(feature = "serde"))]
+    pub fn as_json(&self) -> String {
+        let steps = self.steps.iter().map(|step| {
+            format!(r###"{{
+                "law": "{law}",
+                "path": [{path}],
+                "before": {before},
+                "after": {after},
+                "expression": {expression}
+            }}"###,
+                law = step.law.id(),
+                path = step.path.iter().map(|p| p.to_string()).collect::<Vec<String>>().join(", "),
+                before = step.before.as_json(),
+                after = step.after.as_json(),
+                expression = step.expression.as_json()
+            )
+        }).collect::<Vec<String>>().join(",");
+
+        format!(r###"{{
+            "initial": {initial},
+            "steps": [{steps}]
+        }}"###, initial = self.initial.as_json())
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn as_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// Rewrites expressions by applying a set of laws until none of them matches.
+///
+/// On each step the laws are tried in order against every subterm, from the
+/// root to the leaves and from left to right, and the first match is applied.
+/// Rewrites that lead back to an expression already seen in the derivation
+/// are skipped, so laws like [`Law::Commutativity`] can't loop forever, and so
+/// are rewrites that grow the expression past the size limit, so the two
+/// distribution laws can't keep undoing each other.
+///
+/// ```
+/// use logic_parser::lexing::Lexer;
+/// use logic_parser::parsing::Parser;
+/// use logic_parser::transforming::{Rewriter, Law};
+///
+/// let tokens = Lexer::new().tokenize("~(p -> q)").unwrap();
+/// let ast = Parser::new(&tokens).parse().unwrap();
+///
+/// let derivation = Rewriter::default().rewrite(&ast);
+/// let laws: Vec<Law> = derivation.steps.iter().map(|s| s.law).collect();
+///
+/// assert_eq!(laws, vec![Law::ImplicationElimination, Law::DeMorgan, Law::DoubleNegation]);
+/// assert_eq!(derivation.result().to_string(), "p & ~q");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rewriter {
+    laws: Vec<Law>,
+    max_steps: usize,
+    max_size: usize
+}
+
+impl Rewriter {
+    pub fn new(laws: &[Law]) -> Self {
+        Rewriter { laws: laws.to_vec(), max_steps: 1000, max_size: 1000 }
+    }
+
+    /// Limits the length of the derivation (1000 steps by default).
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Limits the number of nodes a rewrite can grow the expression to (1000
+    /// by default). Rewrites that don't grow the expression are always allowed.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn rewrite(&self, ast: &ASTNode) -> Derivation {
+        let mut derivation = Derivation { initial: ast.clone(), steps: vec![] };
+        let mut seen = HashSet::from([ast.to_string()]);
+
+        while derivation.steps.len() < self.max_steps {
+            let current = derivation.result();
+            let size = current.size();
+            let step = current.paths().into_iter().find_map(|path| {
+                self.laws.iter()
+                    .filter_map(|law| law.apply_at(current, &path))
+                    .filter(|step| {
+                        let grown = step.expression.size();
+                        grown <= size || grown <= self.max_size
+                    })
+                    .find(|step| !seen.contains(&step.expression.to_string()))
+            });
+
+            match step {
+                Some(step) => {
+                    seen.insert(step.expression.to_string());
+                    derivation.steps.push(step);
+                },
+                None => break
+            }
+        }
+        derivation
+    }
+}
+
+/// Eliminates implications, biconditionals and the derived operators, pushes
+/// negations inwards and removes trivial redundancies. Distribution and
+/// commutativity are left out.
+impl Default for Rewriter {
+    fn default() -> Self {
+        Rewriter::new(&[
+            Law::ImplicationElimination,
+            Law::BiconditionalElimination,
+            Law::DerivedOperatorElimination,
+            Law::DoubleNegation,
+            Law::DeMorgan,
+            Law::Negation,
+            Law::Identity,
+            Law::Domination,
+            Law::Idempotence,
+            Law::Absorption,
+        ])
+    }
+}
+
+impl ASTNode {
+    /// Returns the subterm found by following `path` from the root.
+    ///
+    /// Each element of the path selects a child: `0` for the operand of a
+    /// negation or the left side of a binary operator, `1` for the right
+    /// side. The empty path is the root itself.
+    pub fn subterm(&self, path: &[usize]) -> Option<&ASTNode> {
+        match path.split_first() {
+            None => Some(self),
+            Some((i, rest)) => self.children().get(*i)?.subterm(rest)
+        }
+    }
+
+    /// Returns a copy of the expression with the subterm at `path` replaced.
+    pub fn replace_subterm(&self, path: &[usize], replacement: ASTNode) -> Option<ASTNode> {
+        let (i, rest) = match path.split_first() {
+            None => return Some(replacement),
+            Some(split) => split
+        };
+        let mut node = self.clone();
+        match (&mut node, i) {
+            (ASTNode::Not { operand: child, .. }, 0) |
+            (ASTNode::And { left: child, .. }, 0) |
+            (ASTNode::Or { left: child, .. }, 0) |
+            (ASTNode::Implies { left: child, .. }, 0) |
+            (ASTNode::IfAndOnlyIf { left: child, .. }, 0) |
+            (ASTNode::Xor { left: child, .. }, 0) |
+            (ASTNode::Nand { left: child, .. }, 0) |
+            (ASTNode::Nor { left: child, .. }, 0) |
+            (ASTNode::And { right: child, .. }, 1) |
+            (ASTNode::Or { right: child, .. }, 1) |
+            (ASTNode::Implies { right: child, .. }, 1) |
+            (ASTNode::IfAndOnlyIf { right: child, .. }, 1) |
+            (ASTNode::Xor { right: child, .. }, 1) |
+            (ASTNode::Nand { right: child, .. }, 1) |
+            (ASTNode::Nor { right: child, .. }, 1) => {
+                **child = child.replace_subterm(rest, replacement)?;
+            },
+            _ => return None
+        }
+        Some(node)
+    }
+
+    pub(crate) fn children(&self) -> Vec<&ASTNode> {
+        match self {
+            ASTNode::Identifier { .. } | ASTNode::Literal { .. } | ASTNode::Error { .. } => vec![],
+            ASTNode::Not { operand, .. } => vec![operand],
+            ASTNode::And { left, right, .. } |
+            ASTNode::Or { left, right, .. } |
+            ASTNode::Implies { left, right, .. } |
+            ASTNode::IfAndOnlyIf { left, right, .. } |
+            ASTNode::Xor { left, right, .. } |
+            ASTNode::Nand { left, right, .. } |
+            ASTNode::Nor { left, right, .. } => vec![left, right]
+        }
+    }
+
+    /// Paths of every subterm in pre-order.
+    fn paths(&self) -> Vec<Vec<usize>> {
+        let mut paths = vec![vec![]];
+        for (i, child) in self.children().into_iter().enumerate() {
+            paths.extend(child.paths().into_iter().map(|mut p| {
+                p.insert(0, i);
+                p
+            }));
+        }
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::testing::{parse, parse_recovered};
+
+    #[test]
+    fn laws_are_equivalences() {
+        let expressions = [
+            "p -> q", "p <-> q", "~~p", "~(p & q)", "~(p | q)", "p | (q & r)", "(q & r) | p",
+            "p & (q | r)", "p & (p | q)", "(p & q) | p", "p & p", "p | ~p", "~p & p",
+            "~true", "p & true", "false | p", "p & false", "true | p",
+        ];
+        for expression in expressions {
+            let ast = parse(expression);
+            let applied: Vec<_> = Law::ALL.iter().filter_map(|law| law.apply(&ast)).collect();
+            assert!(!applied.is_empty(), "no law matches {expression}");
+            for result in applied {
+                assert!(result.is_equivalent_to(&ast).unwrap(), "{expression} ≢ {result}");
+            }
+        }
+    }
+
+    #[test]
+    fn steps_are_applied_on_subterms() {
+        let ast = parse("p & ~~q");
+        let step = Law::DoubleNegation.apply_at(&ast, &[1]).unwrap();
+
+        assert_eq!(step.before.to_string(), "~~q");
+        assert_eq!(step.after.to_string(), "q");
+        assert_eq!(step.expression.to_string(), "p & q");
+        assert!(Law::DoubleNegation.apply_at(&ast, &[0]).is_none());
+        assert!(Law::DoubleNegation.apply_at(&ast, &[2]).is_none());
+    }
+
+    #[test]
+    fn rewriter_reaches_a_fixpoint() {
+        let ast = parse("(p <-> q) & ~(~p & true)");
+        let derivation = Rewriter::default().rewrite(&ast);
+
+        assert!(derivation.result().is_equivalent_to(&ast).unwrap());
+        assert!(derivation.result().is_nnf(), "{}", derivation.result());
+        for window in derivation.steps.windows(2) {
+            assert!(window[0].expression.is_equivalent_to(&window[1].expression).unwrap());
+        }
+    }
+
+    #[test]
+    fn laws_skip_error_nodes() {
+        let ast = parse_recovered("~~($ | ~$) & ~~p");
+        let derivation = Rewriter::default().rewrite(&ast);
+        assert_eq!(derivation.result().to_string(), "~~(<error> | ~<error>) & p");
+    }
+
+    #[test]
+    fn commutativity_does_not_loop() {
+        let ast = parse("p & q");
+        let derivation = Rewriter::new(&[Law::Commutativity]).rewrite(&ast);
+        assert_eq!(derivation.steps.len(), 1);
+        assert_eq!(derivation.result().to_string(), "q & p");
+    }
+
+    #[test]
+    fn distribution_does_not_grow_without_bound() {
+        let ast = parse("p & (q | r)");
+        let derivation = Rewriter::new(&Law::ALL).rewrite(&ast);
+
+        assert!(derivation.steps.iter().all(|step| step.expression.size() <= 1000));
+        assert!(derivation.result().is_equivalent_to(&ast).unwrap());
+    }
+
+    #[test]
+    fn derivation_is_serialized() {
+        use assert_json::assert_json;
+        let derivation = Rewriter::default().rewrite(&parse("~~p"));
+
+        assert_json!(derivation.as_json().as_str(), {
+            "initial": {
+                "type": "operator.not",
+                "operand": {
+                    "type": "operator.not",
+                    "operand": { "type": "identifier", "name": "p" }
+                }
+            },
+            "steps": [{
+                "law": "double_negation",
+                "path": [],
+                "before": {
+                    "type": "operator.not",
+                    "operand": {
+                        "type": "operator.not",
+                        "operand": { "type": "identifier", "name": "p" }
+                    }
+                },
+                "after": { "type": "identifier", "name": "p" },
+                "expression": { "type": "identifier", "name": "p" }
+            }]
+        });
+    }
+}
@@ -0,0 +1,276 @@
+use crate::parsing::ASTNode;
+
+/// Result of [`ASTNode::simplify`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Simplification {
+    pub result: ASTNode,
+    /// Number of nodes of the original expression
+    pub original_size: usize,
+    /// Number of nodes of the simplified expression
+    pub simplified_size: usize
+}
+
+impl Simplification {
+    /// Whether the simplified expression has fewer nodes than the original.
+    pub fn is_smaller(&self) -> bool {
+        self.simplified_size < self.original_size
+    }
+}
+
+impl ASTNode {
+    /// Number of nodes of the tree.
+    pub fn size(&self) -> usize {
+        match self {
+            ASTNode::Identifier { .. } | ASTNode::Literal { .. } | ASTNode::Error { .. } => 1,
+            ASTNode::Not { operand, .. } => 1 + operand.size(),
+            ASTNode::And { left, right, .. } |
+            ASTNode::Or { left, right, .. } |
+            ASTNode::Implies { left, right, .. } |
+            ASTNode::IfAndOnlyIf { left, right, .. } |
+            ASTNode::Xor { left, right, .. } |
+            ASTNode::Nand { left, right, .. } |
+            ASTNode::Nor { left, right, .. } => 1 + left.size() + right.size()
+        }
+    }
+
+    /// Simplifies the expression with constant propagation and removal of
+    /// trivially redundant subterms.
+    ///
+    /// The rules are applied bottom-up and never make the expression bigger:
+    ///
+    /// - Literals are folded: `p ∧ true` is `p`, `p → false` is `¬p`...
+    /// - Double negations are removed.
+    /// - Chains of `∧` (or `∨`) drop repeated operands, collapse when an operand
+    ///   and its negation are both present (`p ∨ ¬p` is `true`) and absorb
+    ///   operands that contain another one (`p ∧ (p ∨ q)` is `p`).
+    /// - `a → a` and `a ⟷ a` are `true`, `a ⟷ ¬a` is `false`.
+    /// - `⊕`, `↑` and `↓` fold literals and repeated (or complementary)
+    ///   operands the same way: `a ⊕ a` is `false`, `a ↑ a` is `¬a`.
+    ///
+    /// ```
+    /// use logic_parser::lexing::Lexer;
+    /// use logic_parser::parsing::Parser;
+    ///
+    /// let tokens = Lexer::new().tokenize("(p & true) | (q -> q) & ~~r").unwrap();
+    /// let ast = Parser::new(&tokens).parse().unwrap();
+    ///
+    /// let simplification = ast.simplify();
+    /// assert_eq!(simplification.result.to_string(), "p | r");
+    /// assert!(simplification.is_smaller());
+    /// ```
+    pub fn simplify(&self) -> Simplification {
+        let result = simplify(self);
+        Simplification {
+            original_size: self.size(),
+            simplified_size: result.size(),
+            result
+        }
+    }
+}
+
+fn simplify(node: &ASTNode) -> ASTNode {
+    // Rules could drop or fold error nodes, only the subterms without errors
+    // are simplified
+    if node.has_errors() {
+        return node.children().into_iter().enumerate().fold(node.clone(), |result, (i, child)| {
+            result.replace_subterm(&[i], simplify(child)).expect("the child exists")
+        });
+    }
+    match node {
+        ASTNode::Identifier { .. } | ASTNode::Literal { .. } | ASTNode::Error { .. } => node.clone(),
+        ASTNode::Not { operand, .. } => negate(simplify(operand)),
+        ASTNode::And { .. } | ASTNode::Or { .. } => {
+            let conjunction = matches!(node, ASTNode::And { .. });
+            let mut operands = Vec::new();
+            for operand in flatten(node, conjunction) {
+                operands.extend(flatten(&simplify(operand), conjunction).into_iter().cloned());
+            }
+            junction(operands, conjunction)
+        },
+        ASTNode::Implies { left, right, .. } => {
+            match (simplify(left), simplify(right)) {
+                (ASTNode::Literal { value: true, .. }, r) => r,
+                (ASTNode::Literal { value: false, .. }, _) | (_, ASTNode::Literal { value: true, .. }) => {
+                    ASTNode::Literal { value: true, span: None }
+                },
+                (l, ASTNode::Literal { value: false, .. }) => negate(l),
+                (l, r) if l == r => ASTNode::Literal { value: true, span: None },
+                (l, r) => ASTNode::Implies { left: Box::new(l), right: Box::new(r), span: None }
+            }
+        },
+        ASTNode::IfAndOnlyIf { left, right, .. } => {
+            match (simplify(left), simplify(right)) {
+                (ASTNode::Literal { value: true, .. }, other) | (other, ASTNode::Literal { value: true, .. }) => other,
+                (ASTNode::Literal { value: false, .. }, other) | (other, ASTNode::Literal { value: false, .. }) => {
+                    negate(other)
+                },
+                (l, r) if l == r => ASTNode::Literal { value: true, span: None },
+                (l, r) if negate(l.clone()) == r => ASTNode::Literal { value: false, span: None },
+                (l, r) => ASTNode::IfAndOnlyIf { left: Box::new(l), right: Box::new(r), span: None }
+            }
+        },
+        ASTNode::Xor { left, right, .. } => {
+            match (simplify(left), simplify(right)) {
+                (ASTNode::Literal { value: false, .. }, other) | (other, ASTNode::Literal { value: false, .. }) => other,
+                (ASTNode::Literal { value: true, .. }, other) | (other, ASTNode::Literal { value: true, .. }) => {
+                    negate(other)
+                },
+                (l, r) if l == r => ASTNode::Literal { value: false, span: None },
+                (l, r) if negate(l.clone()) == r => ASTNode::Literal { value: true, span: None },
+                (l, r) => ASTNode::Xor { left: Box::new(l), right: Box::new(r), span: None }
+            }
+        },
+        ASTNode::Nand { left, right, .. } => {
+            match (simplify(left), simplify(right)) {
+                (ASTNode::Literal { value: false, .. }, _) | (_, ASTNode::Literal { value: false, .. }) => {
+                    ASTNode::Literal { value: true, span: None }
+                },
+                (ASTNode::Literal { value: true, .. }, other) | (other, ASTNode::Literal { value: true, .. }) => {
+                    negate(other)
+                },
+                (l, r) if l == r => negate(l),
+                (l, r) if negate(l.clone()) == r => ASTNode::Literal { value: true, span: None },
+                (l, r) => ASTNode::Nand { left: Box::new(l), right: Box::new(r), span: None }
+            }
+        },
+        ASTNode::Nor { left, right, .. } => {
+            match (simplify(left), simplify(right)) {
+                (ASTNode::Literal { value: true, .. }, _) | (_, ASTNode::Literal { value: true, .. }) => {
+                    ASTNode::Literal { value: false, span: None }
+                },
+                (ASTNode::Literal { value: false, .. }, other) | (other, ASTNode::Literal { value: false, .. }) => {
+                    negate(other)
+                },
+                (l, r) if l == r => negate(l),
+                (l, r) if negate(l.clone()) == r => ASTNode::Literal { value: false, span: None },
+                (l, r) => ASTNode::Nor { left: Box::new(l), right: Box::new(r), span: None }
+            }
+        }
+    }
+}
+
+fn negate(node: ASTNode) -> ASTNode {
+    match node {
+        ASTNode::Literal { value, .. } => ASTNode::Literal { value: !value, span: None },
+        ASTNode::Not { operand, .. } => *operand,
+        other => ASTNode::Not { operand: Box::new(other), span: None }
+    }
+}
+
+/// Operands of a chain of `∧` (`conjunction`) or `∨`, from left to right.
+fn flatten(node: &ASTNode, conjunction: bool) -> Vec<&ASTNode> {
+    match (node, conjunction) {
+        (ASTNode::And { left, right, .. }, true) | (ASTNode::Or { left, right, .. }, false) => {
+            let mut operands = flatten(left, conjunction);
+            operands.extend(flatten(right, conjunction));
+            operands
+        },
+        _ => vec![node]
+    }
+}
+
+/// Joins already simplified operands with `∧` (`conjunction`) or `∨`.
+fn junction(operands: Vec<ASTNode>, conjunction: bool) -> ASTNode {
+    // `false` for ∧ and `true` for ∨
+    let absorbing = !conjunction;
+    let mut kept: Vec<ASTNode> = Vec::new();
+
+    for operand in operands {
+        match operand {
+            ASTNode::Literal { value, .. } if value == absorbing => return operand,
+            ASTNode::Literal { .. } => continue,
+            _ if kept.contains(&operand) => continue,
+            _ if kept.contains(&negate(operand.clone())) => {
+                return ASTNode::Literal { value: absorbing, span: None }
+            },
+            _ => kept.push(operand)
+        }
+    }
+
+    // Absorption: `a ∧ (a ∨ b)` is `a` and `a ∨ (a ∧ b)` is `a`
+    let is_absorbed = |i: usize, operand: &ASTNode| {
+        let inner = flatten(operand, !conjunction);
+        inner.len() > 1 && kept.iter().enumerate().any(|(j, other)| i != j && inner.contains(&other))
+    };
+    let kept: Vec<ASTNode> = kept.iter().enumerate()
+        .filter(|(i, operand)| !is_absorbed(*i, operand))
+        .map(|(_, operand)| operand.clone())
+        .collect();
+
+    kept.into_iter()
+        .reduce(|l, r| {
+            if conjunction { ASTNode::And { left: Box::new(l), right: Box::new(r), span: None } }
+            else { ASTNode::Or { left: Box::new(l), right: Box::new(r), span: None } }
+        })
+        .unwrap_or(ASTNode::Literal { value: conjunction, span: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsing::testing::{parse, parse_recovered};
+
+    #[test]
+    fn trivial_expressions_are_folded() {
+        let cases = [
+            ("p & true", "p"),
+            ("p | ~p", "true"),
+            ("q -> q", "true"),
+            ("~~p", "p"),
+            ("p -> false", "~p"),
+            ("p <-> ~p", "false"),
+            ("(a | b) <-> true", "a | b"),
+            ("p & q & ~p", "false"),
+            ("p & q & p & (q | r)", "p & q"),
+            ("p | (p & q) | r", "p | r"),
+            ("~(false | ~x)", "x"),
+            ("p ^ p", "false"),
+            ("p ^ true", "~p"),
+            ("p nand true", "~p"),
+            ("(a | b) nor (a | b)", "~(a | b)"),
+            ("p nor ~p", "false"),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(parse(expression).simplify().result.to_string(), expected, "{expression}");
+        }
+    }
+
+    #[test]
+    fn simplification_is_equivalent() {
+        let expressions = [
+            "((p || q)) => (q && ~(r))",
+            "(a & true) | (b & ~b) | (c -> c)",
+            "(tag_a | tag_b) & (tag_a | tag_b | tag_c) & ~~tag_d",
+            "(p <-> q) <-> (q <-> p)",
+            "(p ^ q) nand (r nor false)",
+        ];
+        for expression in expressions {
+            let ast = parse(expression);
+            let simplification = ast.simplify();
+            assert!(simplification.result.is_equivalent_to(&ast).unwrap(), "{expression}");
+            assert!(simplification.simplified_size <= simplification.original_size);
+        }
+    }
+
+    #[test]
+    fn size_reduction_is_reported() {
+        let ast = parse("p & q");
+        let simplification = ast.simplify();
+        assert_eq!(simplification.result, ast);
+        assert!(!simplification.is_smaller());
+
+        let simplification = parse("(p & q) | false").simplify();
+        assert_eq!((simplification.original_size, simplification.simplified_size), (5, 3));
+        assert!(simplification.is_smaller());
+    }
+
+    #[test]
+    fn error_nodes_are_not_folded() {
+        let ast = parse_recovered("($ | ~$) & (p & true)");
+        assert_ne!(ast.subterm(&[0, 0]), ast.subterm(&[0, 1, 0]));
+
+        let simplification = ast.simplify();
+        assert_eq!(simplification.result.to_string(), "(<error> | ~<error>) & p");
+        assert!(simplification.result.has_errors());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::errors::TransformError;
+use crate::evaluating::Model;
+use crate::parsing::ASTNode;
+use super::normal_forms::{Cnf, Literal, Result};
+
+/// Equisatisfiable CNF of an expression, obtained with the Tseitin
+/// transformation.
+///
+/// Every operator of the expression gets a fresh auxiliary identifier that is
+/// constrained to be equivalent to it, so the size of the CNF is linear in the
+/// size of the expression. Auxiliary identifiers are named `_t0`, `_t1`...
+/// adding leading underscores as needed so they never clash with the
+/// identifiers of the expression.
+///
+/// ```
+/// use logic_parser::lexing::Lexer;
+/// use logic_parser::parsing::Parser;
+///
+/// let tokens = Lexer::new().tokenize("(a <-> b) <-> (c <-> d)").unwrap();
+/// let ast = Parser::new(&tokens).parse().unwrap();
+///
+/// let encoding = ast.to_tseitin().unwrap();
+/// assert_eq!(encoding.auxiliaries, vec!["_t0", "_t1", "_t2"]);
+///
+/// let model = encoding.cnf.solve().unwrap();
+/// assert!(ast.evaluate(&encoding.original_model(&model)).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TseitinEncoding {
+    pub cnf: Cnf,
+    /// Identifiers of the original expression
+    pub identifiers: Vec<String>,
+    /// Identifiers introduced by the encoding
+    pub auxiliaries: Vec<String>
+}
+
+impl TseitinEncoding {
+    /// Encodes the expression. Fails if it has error nodes.
+    pub fn from_ast(ast: &ASTNode) -> Result<Self> {
+        let identifiers: Vec<String> = ast.get_ordered_identifiers().into_iter().map(String::from).collect();
+
+        let mut prefix = String::from("_t");
+        while identifiers.iter().any(|i| i.starts_with(&prefix)) {
+            prefix.insert(0, '_');
+        }
+
+        let mut encoder = Encoder { prefix, auxiliaries: vec![], clauses: vec![] };
+        match encoder.encode(ast)? {
+            Encoded::Constant(true) => {},
+            Encoded::Constant(false) => encoder.clauses.push(vec![]),
+            Encoded::Literal(root) => encoder.clauses.push(vec![root])
+        }
+
+        Ok(TseitinEncoding {
+            cnf: Cnf { clauses: encoder.clauses },
+            identifiers,
+            auxiliaries: encoder.auxiliaries
+        })
+    }
+
+    /// Restricts a model of the encoding to the identifiers of the original
+    /// expression. Identifiers missing from `model` are set to `false`.
+    pub fn original_model(&self, model: &Model) -> Model {
+        self.identifiers.iter()
+            .map(|i| (i.clone(), model.get(i).copied().unwrap_or(false)))
+            .collect()
+    }
+}
+
+impl ASTNode {
+    /// Returns the [`TseitinEncoding`] of the expression.
+    pub fn to_tseitin(&self) -> Result<TseitinEncoding> {
+        TseitinEncoding::from_ast(self)
+    }
+}
+
+enum Encoded {
+    Literal(Literal),
+    Constant(bool)
+}
+
+impl Encoded {
+    fn negate(self) -> Encoded {
+        match self {
+            Encoded::Literal(l) => Encoded::Literal(l.negate()),
+            Encoded::Constant(c) => Encoded::Constant(!c)
+        }
+    }
+}
+
+struct Encoder {
+    prefix: String,
+    auxiliaries: Vec<String>,
+    clauses: Vec<Vec<Literal>>
+}
+
+impl Encoder {
+    fn encode(&mut self, node: &ASTNode) -> Result<Encoded> {
+        let encoded = match node {
+            ASTNode::Identifier { name, .. } => Encoded::Literal(Literal::new(name, false)),
+            ASTNode::Error { span } => return Err(TransformError::ErrorNode(*span)),
+            ASTNode::Literal { value, .. } => Encoded::Constant(*value),
+            ASTNode::Not { operand, .. } => self.encode(operand)?.negate(),
+            ASTNode::And { left, right, .. } => {
+                let (l, r) = (self.encode(left)?, self.encode(right)?);
+                self.and(l, r)
+            },
+            ASTNode::Or { left, right, .. } => {
+                let (l, r) = (self.encode(left)?, self.encode(right)?);
+                self.and(l.negate(), r.negate()).negate()
+            },
+            ASTNode::Implies { left, right, .. } => {
+                let (l, r) = (self.encode(left)?, self.encode(right)?);
+                self.and(l, r.negate()).negate()
+            },
+            ASTNode::IfAndOnlyIf { left, right, .. } => {
+                let (l, r) = (self.encode(left)?, self.encode(right)?);
+                self.iff(l, r)
+            },
+            ASTNode::Xor { left, right, .. } => {
+                let (l, r) = (self.encode(left)?, self.encode(right)?);
+                self.iff(l, r).negate()
+            },
+            ASTNode::Nand { left, right, .. } => {
+                let (l, r) = (self.encode(left)?, self.encode(right)?);
+                self.and(l, r).negate()
+            },
+            ASTNode::Nor { left, right, .. } => {
+                let (l, r) = (self.encode(left)?, self.encode(right)?);
+                self.and(l.negate(), r.negate())
+            }
+        };
+        Ok(encoded)
+    }
+
+    fn fresh(&mut self) -> Literal {
+        let name = format!("{}{}", self.prefix, self.auxiliaries.len());
+        self.auxiliaries.push(name.clone());
+        Literal::new(name, false)
+    }
+
+    fn clause(&mut self, literals: &[&Literal]) {
+        let mut clause: Vec<Literal> = Vec::with_capacity(literals.len());
+        for literal in literals {
+            if clause.contains(&literal.negate()) {
+                return;
+            }
+            if !clause.contains(literal) {
+                clause.push((*literal).clone());
+            }
+        }
+        self.clauses.push(clause);
+    }
+
+    /// `x ⟷ a ∧ b` is `(¬x ∨ a) ∧ (¬x ∨ b) ∧ (x ∨ ¬a ∨ ¬b)`
+    fn and(&mut self, a: Encoded, b: Encoded) -> Encoded {
+        match (a, b) {
+            (Encoded::Constant(false), _) | (_, Encoded::Constant(false)) => Encoded::Constant(false),
+            (Encoded::Constant(true), other) | (other, Encoded::Constant(true)) => other,
+            (Encoded::Literal(a), Encoded::Literal(b)) => {
+                let x = self.fresh();
+                self.clause(&[&x.negate(), &a]);
+                self.clause(&[&x.negate(), &b]);
+                self.clause(&[&x, &a.negate(), &b.negate()]);
+                Encoded::Literal(x)
+            }
+        }
+    }
+
+    /// `x ⟷ (a ⟷ b)` is `(¬x ∨ ¬a ∨ b) ∧ (¬x ∨ a ∨ ¬b) ∧ (x ∨ a ∨ b) ∧ (x ∨ ¬a ∨ ¬b)`
+    fn iff(&mut self, a: Encoded, b: Encoded) -> Encoded {
+        match (a, b) {
+            (Encoded::Constant(c), other) | (other, Encoded::Constant(c)) => {
+                if c { other } else { other.negate() }
+            },
+            (Encoded::Literal(a), Encoded::Literal(b)) => {
+                let x = self.fresh();
+                self.clause(&[&x.negate(), &a.negate(), &b]);
+                self.clause(&[&x.negate(), &a, &b.negate()]);
+                self.clause(&[&x, &a, &b]);
+                self.clause(&[&x, &a.negate(), &b.negate()]);
+                Encoded::Literal(x)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::testing::{parse, parse_recovered};
+
+    #[test]
+    fn encoding_is_linear() {
+        // The naive CNF of a chain of n biconditionals has 2^(n - 1) clauses
+        let chain = (0..24).map(|i| format!("p{i}")).collect::<Vec<_>>().join(" <-> ");
+        let encoding = parse(&chain).to_tseitin().unwrap();
+
+        assert_eq!(encoding.auxiliaries.len(), 23);
+        assert_eq!(encoding.cnf.clauses.len(), 23 * 4 + 1);
+    }
+
+    #[test]
+    fn encoding_is_equisatisfiable() {
+        let expressions = [
+            "((p || q)) => (q && ~(r))",
+            "(a <-> b) <-> ~(a <-> b)",
+            "p & ~p",
+            "(x -> y) & (y -> z) & x & ~z",
+            "true -> (false | a)",
+            "~true",
+            "true | q",
+            "(a ^ b) & (a nand b) & (a nor ~b)",
+            "(p ^ q) ^ (p ^ q)",
+        ];
+        for expression in expressions {
+            let ast = parse(expression);
+            let encoding = ast.to_tseitin().unwrap();
+            // Checked by brute force, as the classification goes through the encoding
+            let satisfiable = ast.truth_table().unwrap().rows().any(|row| row.result);
+            match encoding.cnf.solve() {
+                Some(model) => assert!(ast.evaluate(&encoding.original_model(&model)).unwrap(), "{expression}"),
+                None => assert!(!satisfiable, "{expression}")
+            }
+        }
+    }
+
+    #[test]
+    fn auxiliary_identifiers_dont_clash() {
+        let ast = parse("_t0 & (_t1 | __t5)");
+        let encoding = ast.to_tseitin().unwrap();
+
+        assert_eq!(encoding.auxiliaries, vec!["___t0", "___t1"]);
+        assert_eq!(encoding.identifiers, vec!["_t0", "_t1", "__t5"]);
+    }
+
+    #[test]
+    fn error_nodes_result_on_error() {
+        let ast = parse_recovered("p <-> ~$");
+        assert!(matches!(ast.to_tseitin(), Err(TransformError::ErrorNode(Some(_)))));
+    }
+}
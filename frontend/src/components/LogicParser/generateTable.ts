@@ -2,7 +2,8 @@ import type { ASTNode } from '@types';
 import { analizeTree, nodeIsBinaryOperator, nodeIsUnaryOperator } from './analize';
 
 type ASTBinaryOperator = {
-  type: 'operator.implies' | 'operator.iff' | 'operator.or' | 'operator.and',
+  type: 'operator.implies' | 'operator.iff' | 'operator.or' | 'operator.and' |
+    'operator.xor' | 'operator.nand' | 'operator.nor',
   left: ASTNode,
   right: ASTNode
 }
@@ -24,12 +25,20 @@ function operationToString(node: ASTNode): string {
       return `${operationToString(node.left)} v ${operationToString(node.right)}`;
     case 'operator.and':
       return `${operationToString(node.left)} ^ ${operationToString(node.right)}`;
+    case 'operator.xor':
+      return `${operationToString(node.left)} ⊕ ${operationToString(node.right)}`;
+    case 'operator.nand':
+      return `${operationToString(node.left)} ↑ ${operationToString(node.right)}`;
+    case 'operator.nor':
+      return `${operationToString(node.left)} ↓ ${operationToString(node.right)}`;
     case 'operator.not':
       return `!${operationToString(node.operand)}`;
     case 'identifier':
       return node.name;
     case 'literal':
       return node.value.toString();
+    case 'error':
+      return '?';
   }
 }
 
@@ -88,12 +97,20 @@ export function generateTable(tree: ASTNode): string[][] {
             return evaluate(node.left) || evaluate(node.right);
           case 'operator.and':
             return evaluate(node.left) && evaluate(node.right);
+          case 'operator.xor':
+            return evaluate(node.left) !== evaluate(node.right);
+          case 'operator.nand':
+            return !(evaluate(node.left) && evaluate(node.right));
+          case 'operator.nor':
+            return !(evaluate(node.left) || evaluate(node.right));
           case 'operator.not':
             return !evaluate(node.operand);
           case 'identifier':
             return valuesMap.get(node.name) ?? false;
           case 'literal':
             return node.value;
+          case 'error':
+            throw new Error('error nodes have no truth value');
         }
       }
       row.push(evaluate(operation) ? '1' : '0');
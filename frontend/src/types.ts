@@ -14,25 +14,39 @@ export type ProjectPage = {
   page: ProjectPageInstance
 }
 
+/** Source range `[start, end)` of a parsed node, parentheses included */
+export type ASTSpan = [start: number, end: number]
+
 export type ASTBinaryOperator = {
-  type: 'operator.implies' | 'operator.iff' | 'operator.or' | 'operator.and',
+  type: 'operator.implies' | 'operator.iff' | 'operator.or' | 'operator.and' |
+    'operator.xor' | 'operator.nand' | 'operator.nor',
   left: ASTNode,
-  right: ASTNode
+  right: ASTNode,
+  span?: ASTSpan
 }
 
 export type ASTLiteral = {
   type: 'literal',
-  value: boolean
+  value: boolean,
+  span?: ASTSpan
 }
 
 export type ASTIdentifier = {
   type: 'identifier',
-  name: string
+  name: string,
+  span?: ASTSpan
 }
 
 export type ASTUnaryOperator = {
   type: 'operator.not',
-  operand: ASTNode
+  operand: ASTNode,
+  span?: ASTSpan
+}
+
+/** Part of the source that couldn't be parsed, only in recovered trees */
+export type ASTError = {
+  type: 'error',
+  span?: ASTSpan
 }
 
 export type ASTOperator = ASTBinaryOperator | ASTUnaryOperator;
@@ -40,21 +54,64 @@ export type ASTOperator = ASTBinaryOperator | ASTUnaryOperator;
 export type ASTNode =
   ASTOperator |
   ASTLiteral |
-  ASTIdentifier;
+  ASTIdentifier |
+  ASTError;
 
 export type LogicParserSuccessResult = {
   status: 'success',
   ast: ASTNode
 }
 
+/** Location of an error, lines and columns start at 1 */
+export type SourcePosition = {
+  line: number,
+  column: number,
+  utf16_column: number,
+  offset: number,
+  utf16_offset: number
+}
+
+/** Edit that fixes an error, replacing the text between `start` and `end` */
+export type SourceFix = {
+  message: string,
+  span: [start: number, end: number],
+  replacement: string,
+  start: SourcePosition,
+  end: SourcePosition
+}
+
 export type LogicParserErrorResult = {
   status: 'error',
   error: string,
-  span: [start: number, end: number]
+  span: [start: number, end: number],
+  /** Stable error code, like 'E0201' */
+  code: string,
+  help: string | null,
+  start: SourcePosition,
+  end: SourcePosition,
+  /** Tokens that would have been accepted, like "')'" or 'identifier' */
+  expected: string[],
+  fixes: SourceFix[]
 }
 
 export type LogicParsingResult = LogicParserSuccessResult | LogicParserErrorResult;
 
+export type Diagnostic = {
+  code: string,
+  message: string,
+  help: string | null,
+  span: ASTSpan,
+  start: SourcePosition,
+  end: SourcePosition,
+  expected: string[],
+  fixes: SourceFix[]
+}
+
+export type LogicParserRecoveryResult = {
+  ast: ASTNode,
+  errors: Diagnostic[]
+}
+
 type TokenIdentifier = {
   kind: 'identifier',
   value: string